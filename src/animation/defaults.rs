@@ -39,6 +39,13 @@ pub fn default_character_animations(asset_server: &AssetServer) -> AvatarAnimati
             gltf: gltf.clone(),
         },
     );
+    map.insert(
+        AnimationName::Run,
+        AvatarAnimation {
+            clip: asset_server.load(format!("{}#Animation4", ASSET)),
+            gltf: gltf.clone(),
+        },
+    );
     map.insert(
         AnimationName::Walk,
         AvatarAnimation {
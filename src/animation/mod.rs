@@ -10,9 +10,19 @@ use weights::{AnimationWeights, TargetAnimationWeights};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum AnimationName {
+    Climb,
+    Crouch,
+    CrouchWalk,
+    Dash,
     Falling,
+    /// Played while flying, falls back to [AnimationName::Falling] if not provided.
+    Float,
     #[default]
     Idle,
+    Run,
+    Swim,
+    /// Played while idle in water.
+    TreadWater,
     Walk,
     WalkLeft,
     WalkRight,
@@ -1,8 +1,11 @@
 use bevy::{animation::ActiveAnimation, prelude::*, utils::HashMap};
+use bevy_tnua::prelude::*;
 
 use super::{AnimationName, AvatarAnimationNodes};
 use crate::{
-    player::{PlayerAvatar, PlayerBody},
+    movement::PlayerMovementMode,
+    player::{PlayerAvatar, PlayerBody, PlayerCrouch, PlayerSpeed, PlayerSprintSpeed},
+    pose::ApplyPose,
     velocity::AverageVelocity,
 };
 
@@ -19,8 +22,21 @@ const WEIGHT_THRESHOLD: f32 = 0.02;
 
 pub(crate) fn play_avatar_animations(
     time: Res<Time>,
-    players: Query<&Transform, With<PlayerBody>>,
-    mut avatars: Query<(&AvatarAnimationNodes, &AverageVelocity, &Parent), With<PlayerAvatar>>,
+    players: Query<
+        (
+            &Transform,
+            &PlayerMovementMode,
+            &PlayerSpeed,
+            &PlayerSprintSpeed,
+            &PlayerCrouch,
+            Option<&TnuaController>,
+        ),
+        With<PlayerBody>,
+    >,
+    mut avatars: Query<
+        (&AvatarAnimationNodes, &AverageVelocity, &Parent),
+        (With<PlayerAvatar>, Without<ApplyPose>),
+    >,
     mut animation_players: Query<(
         &mut AnimationWeights,
         &TargetAnimationWeights,
@@ -35,7 +51,8 @@ pub(crate) fn play_avatar_animations(
             continue;
         };
 
-        let Ok(transform) = players.get(**parent) else {
+        let Ok((transform, mode, speed, sprint_speed, crouch, controller)) = players.get(**parent)
+        else {
             continue;
         };
 
@@ -64,8 +81,61 @@ pub(crate) fn play_avatar_animations(
         let forward = vel_forward.element_sum() * VELOCITY_FACTOR;
         let left = vel_left.element_sum() * VELOCITY_FACTOR;
 
+        // Ground animations fade out while flying.
+        let fly_animation = if nodes.0.contains_key(&AnimationName::Float) {
+            Some(AnimationName::Float)
+        } else if nodes.0.contains_key(&AnimationName::Falling) {
+            Some(AnimationName::Falling)
+        } else {
+            None
+        };
+
+        let fly_amount = match (&fly_animation, mode) {
+            (Some(_), PlayerMovementMode::Fly { .. }) => 1.0,
+            _ => 0.0,
+        };
+
+        // Ground animations fade out while swimming.
+        let has_swim = nodes.0.contains_key(&AnimationName::Swim)
+            && nodes.0.contains_key(&AnimationName::TreadWater);
+
+        let swim_amount = match mode {
+            PlayerMovementMode::Swim { .. } if has_swim => 1.0,
+            _ => 0.0,
+        };
+
+        // Ground animations fade out while climbing.
+        let has_climb = nodes.0.contains_key(&AnimationName::Climb);
+
+        let climb_amount = match mode {
+            PlayerMovementMode::Climb { .. } if has_climb => 1.0,
+            _ => 0.0,
+        };
+
+        // Ground animations fade out while dashing.
+        let has_dash = nodes.0.contains_key(&AnimationName::Dash);
+
+        let dashing = controller
+            .is_some_and(|controller| controller.action_name() == Some(TnuaBuiltinDash::NAME));
+
+        let dash_amount = if has_dash && dashing { 1.0 } else { 0.0 };
+
+        let ground_amount = 1.0 - fly_amount - swim_amount - climb_amount - dash_amount;
+
+        // Standing animations fade out as the player crouches.
+        let has_crouch = nodes.0.contains_key(&AnimationName::Crouch)
+            && nodes.0.contains_key(&AnimationName::CrouchWalk);
+
+        let crouch_amount = if has_crouch {
+            crouch.amount * ground_amount
+        } else {
+            0.0
+        };
+
+        let stand_amount = ground_amount - crouch_amount;
+
         // Left walk.
-        let mut l_walk_weight = left.max(0.0);
+        let mut l_walk_weight = left.max(0.0) * stand_amount;
 
         apply_weight(
             AnimationName::WalkLeft,
@@ -77,7 +147,7 @@ pub(crate) fn play_avatar_animations(
         );
 
         // Right walk.
-        let mut r_walk_weight = left.min(0.0).abs();
+        let mut r_walk_weight = left.min(0.0).abs() * stand_amount;
 
         apply_weight(
             AnimationName::WalkRight,
@@ -88,12 +158,35 @@ pub(crate) fn play_avatar_animations(
             &mut weights,
         );
 
+        // Run.
+        // Blends in as the forward speed goes from walking to sprinting speed.
+        let mut run_weight = 0.0;
+
+        if nodes.0.contains_key(&AnimationName::Run) {
+            let speed_forward = vel_forward.element_sum().abs();
+            let speed_range = (sprint_speed.0 - speed.0).max(f32::EPSILON);
+
+            run_weight = ((speed_forward - speed.0) / speed_range).clamp(0.0, 1.0) * stand_amount;
+
+            let run = apply_weight(
+                AnimationName::Run,
+                &mut run_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+
+            let run_speed = speed_forward / sprint_speed.0.max(f32::EPSILON);
+            run.set_speed(run_speed.max(0.5));
+        }
+
         // Walk.
-        let mut walk_weight = forward.abs();
+        let mut walk_weight = (forward.abs() - left.abs()) * stand_amount;
 
-        walk_weight -= left.abs();
         walk_weight -= l_walk_weight;
         walk_weight -= r_walk_weight;
+        walk_weight -= run_weight;
 
         let walk = apply_weight(
             AnimationName::Walk,
@@ -110,6 +203,119 @@ pub(crate) fn play_avatar_animations(
             walk.set_speed(-1.0);
         }
 
+        // Crouch.
+        let mut crouch_walk_weight = 0.0;
+        let mut crouch_weight = 0.0;
+
+        if has_crouch {
+            crouch_walk_weight = (forward.abs() + left.abs()).min(1.0) * crouch_amount;
+
+            let crouch_walk = apply_weight(
+                AnimationName::CrouchWalk,
+                &mut crouch_walk_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+
+            if forward.is_sign_positive() {
+                crouch_walk.set_speed(1.0);
+            } else {
+                crouch_walk.set_speed(-1.0);
+            }
+
+            crouch_weight = crouch_amount - crouch_walk_weight;
+
+            apply_weight(
+                AnimationName::Crouch,
+                &mut crouch_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+        }
+
+        // Fly.
+        let mut fly_weight = 0.0;
+
+        if let Some(name) = fly_animation {
+            fly_weight = fly_amount;
+
+            apply_weight(
+                name,
+                &mut fly_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+        }
+
+        // Swim.
+        let mut swim_weight = 0.0;
+        let mut tread_weight = 0.0;
+
+        if has_swim {
+            swim_weight = (avg.velocity.length() * VELOCITY_FACTOR).min(1.0) * swim_amount;
+
+            apply_weight(
+                AnimationName::Swim,
+                &mut swim_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+
+            tread_weight = swim_amount - swim_weight;
+
+            apply_weight(
+                AnimationName::TreadWater,
+                &mut tread_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+        }
+
+        // Climb.
+        let mut climb_weight = 0.0;
+
+        if has_climb {
+            climb_weight = climb_amount;
+
+            let climb = apply_weight(
+                AnimationName::Climb,
+                &mut climb_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+
+            // Pause the animation while not moving along the ladder.
+            climb.set_speed((avg.velocity.y * VELOCITY_FACTOR).clamp(-1.0, 1.0));
+        }
+
+        // Dash.
+        let mut dash_weight = 0.0;
+
+        if has_dash {
+            dash_weight = dash_amount;
+
+            apply_weight(
+                AnimationName::Dash,
+                &mut dash_weight,
+                alpha,
+                &mut player,
+                nodes,
+                &mut weights,
+            );
+        }
+
         // Other.
         let mut idle_weight = 1.0;
 
@@ -135,6 +341,14 @@ pub(crate) fn play_avatar_animations(
         idle_weight -= l_walk_weight;
         idle_weight -= r_walk_weight;
         idle_weight -= walk_weight;
+        idle_weight -= run_weight;
+        idle_weight -= crouch_walk_weight;
+        idle_weight -= crouch_weight;
+        idle_weight -= fly_weight;
+        idle_weight -= swim_weight;
+        idle_weight -= tread_weight;
+        idle_weight -= climb_weight;
+        idle_weight -= dash_weight;
 
         apply_weight(
             AnimationName::Idle,
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    calibration::EYE_HEIGHT_RATIO,
+    eye_offset::EyeOffset,
+    player::{
+        player_collider, AvatarScaling, PlayerAvatar, PlayerBody, PlayerCrouch, PlayerHeight,
+        PlayerStepHeight, PlayerWidth,
+    },
+};
+
+/// Marks an avatar that has already been sized.
+#[derive(Component)]
+pub(crate) struct AvatarScaled;
+
+/// Sizes the avatar and the player to match each other,
+/// once the avatar's [EyeOffset] is known.
+pub(crate) fn scale_avatars(
+    mut avatars: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut EyeOffset,
+            &mut PlayerHeight,
+            &Parent,
+        ),
+        (With<PlayerAvatar>, Without<AvatarScaled>),
+    >,
+    mut commands: Commands,
+    mut players: Query<
+        (
+            &AvatarScaling,
+            &mut PlayerCrouch,
+            &mut PlayerHeight,
+            &PlayerStepHeight,
+            &PlayerWidth,
+        ),
+        (With<PlayerBody>, Without<PlayerAvatar>),
+    >,
+) {
+    for (entity, mut avatar_tr, mut offset, mut avatar_height, parent) in avatars.iter_mut() {
+        let Ok((scaling, mut crouch, mut height, step, width)) = players.get_mut(parent.get())
+        else {
+            continue;
+        };
+
+        let eye_height = offset.y;
+
+        if eye_height <= 0.0 {
+            warn_once!("Avatar eyes are below its feet, skipping scaling");
+            continue;
+        }
+
+        commands.entity(entity).insert(AvatarScaled);
+
+        match scaling {
+            AvatarScaling::ScaleAvatar => {
+                let scale = height.0 * EYE_HEIGHT_RATIO / eye_height;
+                avatar_tr.scale = Vec3::splat(scale);
+                offset.0 *= scale;
+            }
+            AvatarScaling::ResizeCollider => {
+                let new_height = eye_height / EYE_HEIGHT_RATIO;
+                height.0 = new_height;
+                avatar_height.0 = new_height;
+
+                // Keep crouching from making the player taller.
+                crouch.height = crouch.height.min(new_height);
+
+                if !crouch.crouching {
+                    commands
+                        .entity(parent.get())
+                        .insert(player_collider(new_height, width.0, step.0));
+                }
+            }
+            AvatarScaling::None => {}
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+/// Ratio of eye height to total height, for an average person.
+pub(crate) const EYE_HEIGHT_RATIO: f32 = 0.94;
+
+/// Calibration of the user's physical height in XR.
+/// Apps can save this resource and restore it on the next launch.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct XrCalibration {
+    /// Height of the HMD above the floor, in meters, while the user is standing.
+    /// Once set, this height is placed at the avatar's eye level,
+    /// so that physically crouching lowers the view.
+    /// If `None`, the HMD is always placed at the avatar's eye level.
+    pub standing_eye_height: Option<f32>,
+    /// If `true`, `seated_offset` is added to the HMD height.
+    /// Only used once calibrated.
+    pub seated: bool,
+    /// Vertical offset, in meters, added to the HMD height while seated.
+    pub seated_offset: f32,
+}
+
+impl Default for XrCalibration {
+    fn default() -> Self {
+        Self {
+            standing_eye_height: None,
+            seated: false,
+            seated_offset: 0.5,
+        }
+    }
+}
+
+impl XrCalibration {
+    /// Calibrates from the user's total height, in meters.
+    pub fn set_user_height(&mut self, height: f32) {
+        self.standing_eye_height = Some(height * EYE_HEIGHT_RATIO);
+    }
+
+    /// Height of the HMD, in meters, that is placed at the avatar's eye level,
+    /// given the current height of the HMD.
+    pub fn reference_height(&self, hmd_height: f32) -> f32 {
+        let Some(standing) = self.standing_eye_height else {
+            return hmd_height;
+        };
+
+        let offset = if self.seated { self.seated_offset } else { 0.0 };
+        standing - offset
+    }
+}
+
+/// Calibrates [XrCalibration] from the current HMD height.
+/// Should be sent while the user is standing up straight.
+#[derive(Event, Debug, Default)]
+pub struct CalibrateXrHeight;
+
+#[cfg(feature = "xr")]
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn calibrate_xr_height(
+    mut calibration: ResMut<XrCalibration>,
+    mut events: EventReader<CalibrateXrHeight>,
+    views: Res<bevy_mod_openxr::resources::OxrViews>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    events.clear();
+
+    let Some(view) = views.first() else {
+        warn!("Cannot calibrate height without an HMD view");
+        return;
+    };
+
+    calibration.standing_eye_height = Some(view.pose.position.y);
+    calibration.seated = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uncalibrated_follows_hmd() {
+        let mut calibration = XrCalibration::default();
+        assert_eq!(calibration.reference_height(1.2), 1.2);
+
+        calibration.seated = true;
+        assert_eq!(calibration.reference_height(1.2), 1.2);
+    }
+
+    #[test]
+    fn calibrated_uses_standing_height() {
+        let mut calibration = XrCalibration {
+            standing_eye_height: Some(1.6),
+            ..default()
+        };
+        assert_eq!(calibration.reference_height(1.2), 1.6);
+
+        calibration.seated = true;
+        assert_eq!(
+            calibration.reference_height(1.2),
+            1.6 - calibration.seated_offset
+        );
+    }
+}
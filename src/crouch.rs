@@ -0,0 +1,111 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    movement::PlayerInputState,
+    player::{
+        float_height, player_collider, PlayerAvatar, PlayerBody, PlayerCrouch, PlayerHeight,
+        PlayerStepHeight, PlayerWidth,
+    },
+};
+
+const CROUCH_LERP_FACTOR: f32 = 10.0;
+
+pub(crate) fn crouch_player(
+    mut commands: Commands,
+    mut players: Query<
+        (
+            Entity,
+            &Position,
+            &Rotation,
+            &Collider,
+            &PlayerInputState,
+            &PlayerHeight,
+            &PlayerStepHeight,
+            &PlayerWidth,
+            &mut PlayerCrouch,
+        ),
+        With<PlayerBody>,
+    >,
+    spatial_query: SpatialQuery,
+) {
+    for (entity, position, rotation, collider, input, height, step, width, mut crouch) in
+        players.iter_mut()
+    {
+        let wants_crouch = if crouch.toggle {
+            if input.crouch && !crouch.pressed {
+                !crouch.crouching
+            } else {
+                crouch.crouching
+            }
+        } else {
+            input.crouch
+        };
+
+        crouch.pressed = input.crouch;
+
+        if wants_crouch == crouch.crouching {
+            continue;
+        }
+
+        if !wants_crouch {
+            // Only stand up if there is room above the player's head.
+            let hit = spatial_query.cast_shape(
+                collider,
+                position.0,
+                rotation.0,
+                Dir3::new_unchecked(rotation.0 * Vec3::Y),
+                height.0 - crouch.height,
+                true,
+                SpatialQueryFilter::from_excluded_entities([entity]),
+            );
+
+            if hit.is_some() {
+                continue;
+            }
+        }
+
+        crouch.crouching = wants_crouch;
+
+        let new_height = if wants_crouch {
+            crouch.height
+        } else {
+            height.0
+        };
+
+        commands
+            .entity(entity)
+            .insert(player_collider(new_height, width.0, step.0));
+    }
+}
+
+/// Smoothly moves the avatar between standing and crouched offsets,
+/// keeping its feet on the ground.
+pub(crate) fn apply_crouch_offset(
+    mut avatars: Query<&mut Transform, With<PlayerAvatar>>,
+    mut players: Query<
+        (
+            &mut PlayerCrouch,
+            &PlayerHeight,
+            &PlayerStepHeight,
+            &Children,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+) {
+    let lerp_factor = (time.delta_seconds() * CROUCH_LERP_FACTOR).min(1.0);
+
+    for (mut crouch, height, step, children) in players.iter_mut() {
+        let target = if crouch.crouching { 1.0 } else { 0.0 };
+        crouch.amount += (target - crouch.amount) * lerp_factor;
+
+        let current_height = crouch.current_height(height.0);
+
+        for child in children.iter() {
+            if let Ok(mut avatar_tr) = avatars.get_mut(*child) {
+                avatar_tr.translation.y = -float_height(current_height, step.0);
+            }
+        }
+    }
+}
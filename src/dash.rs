@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use bevy_tnua::prelude::*;
+
+use crate::{
+    movement::{PlayerInputState, PlayerMovementMode},
+    player::{PlayerBody, PlayerDash},
+};
+
+/// Sent when a player starts a dash.
+#[derive(Event, Debug)]
+pub struct PlayerDashEvent {
+    pub player: Entity,
+    pub direction: Vec3,
+}
+
+pub(crate) fn dash_player(
+    mut events: EventWriter<PlayerDashEvent>,
+    mut players: Query<
+        (
+            Entity,
+            &Transform,
+            &PlayerInputState,
+            &PlayerMovementMode,
+            &mut PlayerDash,
+            &mut TnuaController,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    for (entity, transform, input, mode, mut dash, mut controller) in players.iter_mut() {
+        if *mode != PlayerMovementMode::Walk {
+            continue;
+        }
+
+        let ready = dash
+            .last_dash
+            .map_or(true, |last_dash| now - last_dash >= dash.cooldown);
+
+        if input.dash && ready {
+            let airborne = controller.is_airborne().unwrap_or(false);
+
+            if dash.allow_in_air || !airborne {
+                let dir_forward = transform.rotation.mul_vec3(Vec3::NEG_Z);
+                let dir_left = transform.rotation.mul_vec3(Vec3::NEG_X);
+
+                let mut direction = Vec3::ZERO;
+
+                direction += dir_forward * input.forward;
+                direction += dir_left * input.left;
+
+                // Dash forwards if there is no movement input.
+                let direction = direction.try_normalize().unwrap_or(dir_forward);
+
+                dash.last_dash = Some(now);
+                dash.displacement = direction * dash.distance;
+
+                events.send(PlayerDashEvent {
+                    player: entity,
+                    direction,
+                });
+            }
+        }
+
+        let Some(last_dash) = dash.last_dash else {
+            continue;
+        };
+
+        if now - last_dash < dash.duration {
+            controller.action(TnuaBuiltinDash {
+                allow_in_air: dash.allow_in_air,
+                displacement: dash.displacement,
+                speed: dash.distance / dash.duration.max(f32::EPSILON),
+                ..default()
+            });
+        }
+    }
+}
@@ -1,5 +1,15 @@
-use bevy::prelude::*;
-use bevy_vrm::{first_person::SetupFirstPerson, loader::Vrm};
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_vrm::{
+    first_person::{FirstPersonFlag, SetupFirstPerson, RENDER_LAYERS},
+    loader::Vrm,
+};
+
+use crate::{
+    input::XrUser,
+    player::{PlayerBody, PlayerCamera},
+    remote::RemotePlayer,
+    render_layers::{player_layers, PLAYER_LAYER_START},
+};
 
 #[derive(Component)]
 pub struct FirstPerson;
@@ -11,12 +21,123 @@ pub(crate) fn setup_first_person(
 ) {
     for event in events.read() {
         if let AssetEvent::LoadedWithDependencies { id } = event {
-            let (ent, _) = avatars
+            for (ent, _) in avatars.iter().filter(|(_, handle)| handle.id() == *id) {
+                writer.send(SetupFirstPerson(ent));
+            }
+        }
+    }
+}
+
+/// Render layers of a player's avatar, so that each player only hides their own head.
+/// Cameras other than the player cameras need the `third_person` layer to see the player's head.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PlayerRenderLayers {
+    /// Layer of avatar meshes only seen by the player themselves.
+    pub first_person: usize,
+    /// Layer of avatar meshes hidden from the player themselves, such as their head.
+    pub third_person: usize,
+}
+
+pub(crate) fn assign_player_render_layers(
+    mut commands: Commands,
+    mut next_player: Local<usize>,
+    players: Query<
+        Entity,
+        (
+            With<PlayerBody>,
+            Without<PlayerRenderLayers>,
+            Without<RemotePlayer>,
+        ),
+    >,
+) {
+    for entity in players.iter() {
+        let (first_person, third_person) = player_layers(*next_player);
+        *next_player += 1;
+
+        commands.entity(entity).insert(PlayerRenderLayers {
+            first_person,
+            third_person,
+        });
+    }
+}
+
+/// Moves the first person meshes set up by [bevy_vrm] onto their player's own layers.
+pub(crate) fn apply_avatar_render_layers(
+    avatars: Query<&Parent, With<FirstPerson>>,
+    mut meshes: Query<(Entity, &mut RenderLayers), Changed<RenderLayers>>,
+    parents: Query<&Parent>,
+    players: Query<&PlayerRenderLayers>,
+) {
+    let first_person_only = &RENDER_LAYERS[&FirstPersonFlag::FirstPersonOnly];
+    let third_person_only = &RENDER_LAYERS[&FirstPersonFlag::ThirdPersonOnly];
+
+    for (entity, mut layers) in meshes.iter_mut() {
+        let first_person = *layers == *first_person_only;
+
+        if !first_person && *layers != *third_person_only {
+            continue;
+        }
+
+        let Some(player_layers) = parents
+            .iter_ancestors(entity)
+            .find_map(|ancestor| avatars.get(ancestor).ok())
+            .and_then(|body| players.get(body.get()).ok())
+        else {
+            continue;
+        };
+
+        *layers = RenderLayers::layer(if first_person {
+            player_layers.first_person
+        } else {
+            player_layers.third_person
+        });
+    }
+}
+
+/// Shows each player camera its own player's first person layer,
+/// and the third person layers of all other players.
+pub(crate) fn update_camera_render_layers(
+    mut cameras: Query<(Entity, &mut RenderLayers, Option<&Parent>), With<Camera>>,
+    player_cameras: Query<(), With<PlayerCamera>>,
+    players: Query<(Entity, Has<XrUser>, &PlayerRenderLayers)>,
+    #[cfg(feature = "xr")] xr_cameras: Query<(), With<bevy_mod_xr::camera::XrCamera>>,
+) {
+    for (entity, mut layers, parent) in cameras.iter_mut() {
+        #[cfg(feature = "xr")]
+        let is_xr = xr_cameras.contains(entity);
+        #[cfg(not(feature = "xr"))]
+        let is_xr = false;
+
+        let owner = if player_cameras.contains(entity) {
+            parent.map(|parent| parent.get())
+        } else if is_xr {
+            players
                 .iter()
-                .find(|(_, handle)| handle.id() == *id)
-                .expect("Avatar not found");
+                .find(|(_, xr_user, _)| *xr_user)
+                .map(|(player, ..)| player)
+        } else {
+            None
+        };
+
+        let Some(owner) = owner else {
+            continue;
+        };
+
+        let mut new_layers = layers
+            .iter()
+            .filter(|layer| *layer < PLAYER_LAYER_START)
+            .fold(RenderLayers::none(), |acc, layer| acc.with(layer));
+
+        for (player, _, player_layers) in players.iter() {
+            new_layers = new_layers.with(if player == owner {
+                player_layers.first_person
+            } else {
+                player_layers.third_person
+            });
+        }
 
-            writer.send(SetupFirstPerson(ent));
+        if new_layers != *layers {
+            *layers = new_layers;
         }
     }
 }
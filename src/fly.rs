@@ -0,0 +1,61 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    movement::{PlayerInputState, PlayerMovementMode},
+    player::{PlayerBody, PlayerCamera, PlayerSpeed, PlayerSprintSpeed},
+};
+
+const FLY_ACCELERATION: f32 = 10.0;
+
+pub(crate) fn fly_player(
+    cameras: Query<&Transform, (With<PlayerCamera>, Without<PlayerBody>)>,
+    mut players: Query<
+        (
+            &Transform,
+            &Children,
+            &PlayerInputState,
+            &PlayerMovementMode,
+            &PlayerSpeed,
+            &PlayerSprintSpeed,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+) {
+    let lerp_factor = (time.delta_seconds() * FLY_ACCELERATION).min(1.0);
+
+    for (transform, children, input, mode, speed, sprint_speed, mut linvel) in players.iter_mut() {
+        if !matches!(mode, PlayerMovementMode::Fly { .. }) {
+            continue;
+        }
+
+        let camera_rotation = children
+            .iter()
+            .find_map(|c| cameras.get(*c).ok())
+            .map(|camera_tr| camera_tr.rotation)
+            .unwrap_or_default();
+
+        let rotation = transform.rotation * camera_rotation;
+
+        let dir_forward = rotation.mul_vec3(Vec3::NEG_Z);
+        let dir_left = rotation.mul_vec3(Vec3::NEG_X);
+
+        let mut move_direction = Vec3::ZERO;
+
+        move_direction += dir_forward * input.forward;
+        move_direction += dir_left * input.left;
+        move_direction += *transform.up() * input.up;
+
+        let speed = if input.sprint {
+            sprint_speed.0
+        } else {
+            speed.0
+        };
+
+        let desired_velocity = move_direction.normalize_or_zero() * speed;
+
+        linvel.0 = linvel.0.lerp(desired_velocity, lerp_factor);
+    }
+}
@@ -0,0 +1,86 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::TnuaProximitySensor;
+
+use crate::player::PlayerBody;
+
+/// Custom gravity for a player, replacing the direction of the global [Gravity].
+/// The magnitude of the global [Gravity] is kept.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum PlayerGravity {
+    /// Constant gravity direction.
+    Direction(Dir3),
+    /// Gravity pulling towards a point, such as the center of a planet.
+    Point(Vec3),
+}
+
+impl PlayerGravity {
+    /// Up direction at a given position.
+    pub fn up(&self, position: Vec3) -> Vec3 {
+        match self {
+            Self::Direction(dir) => -dir.as_vec3(),
+            Self::Point(center) => (position - *center).try_normalize().unwrap_or(Vec3::Y),
+        }
+    }
+}
+
+/// Height of a position, measured against the player's gravity.
+pub(crate) fn height_above(gravity: Option<&PlayerGravity>, position: Vec3) -> f32 {
+    match gravity {
+        None => position.y,
+        Some(PlayerGravity::Direction(dir)) => -position.dot(dir.as_vec3()),
+        Some(PlayerGravity::Point(center)) => position.distance(*center),
+    }
+}
+
+/// The player's orientation frame, rotating the Y axis to the player's up direction.
+/// The player's yaw is applied within this frame.
+#[derive(Component, Default)]
+pub(crate) struct PlayerUp(pub Quat);
+
+pub(crate) fn update_player_up(
+    mut players: Query<
+        (
+            &Transform,
+            Option<&PlayerGravity>,
+            &mut PlayerUp,
+            &mut TnuaProximitySensor,
+        ),
+        With<PlayerBody>,
+    >,
+) {
+    for (transform, gravity, mut frame, mut sensor) in players.iter_mut() {
+        let up = gravity.map_or(Vec3::Y, |g| g.up(transform.translation));
+
+        // Rotate the previous frame, to avoid introducing twist.
+        let prev_up = frame.0 * Vec3::Y;
+        frame.0 = (Quat::from_rotation_arc(prev_up, up) * frame.0).normalize();
+
+        sensor.cast_direction = Dir3::new_unchecked(-up);
+    }
+}
+
+/// Applies custom gravity, by cancelling out the global gravity.
+/// Runs in the [PhysicsSchedule], so that it is applied once per physics step.
+pub(crate) fn apply_player_gravity(
+    gravity: Res<Gravity>,
+    mut players: Query<
+        (
+            &Transform,
+            &PlayerGravity,
+            Option<&GravityScale>,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time<Physics>>,
+) {
+    let magnitude = gravity.0.length();
+
+    for (transform, player_gravity, scale, mut linvel) in players.iter_mut() {
+        let scale = scale.map_or(1.0, |s| s.0);
+        let custom = -player_gravity.up(transform.translation) * magnitude;
+
+        linvel.0 += (custom - gravity.0) * scale * time.delta_seconds();
+    }
+}
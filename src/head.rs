@@ -1,7 +1,13 @@
 use bevy::prelude::*;
 use bevy_vrm::BoneName;
 
-use crate::{eye_offset::EyeOffset, player::PlayerCamera};
+use crate::{
+    eye_offset::EyeOffset,
+    player::{PlayerCamera, PlayerCrouch, PlayerHeight, PlayerStepHeight},
+    remote::RemotePlayer,
+};
+
+const STEP_SMOOTHING_FACTOR: f32 = 15.0;
 
 #[derive(Component)]
 pub struct AvatarHead(pub Entity);
@@ -40,16 +46,38 @@ fn is_child(target_child: Entity, target_parent: Entity, parents: &Query<&Parent
 #[derive(Component)]
 pub struct BaseRotation(pub Quat);
 
+/// Smoothed vertical position of the player, used to smooth out camera movement
+/// when walking up or down steps.
+#[derive(Component, Default)]
+pub(crate) struct StepSmoothing(Option<f32>);
+
 pub(crate) fn rotate_avatar_head(
-    avatars: Query<(&AvatarHead, &EyeOffset)>,
+    avatars: Query<
+        (&AvatarHead, &EyeOffset, &Transform, &Parent),
+        (Without<BoneName>, Without<PlayerCamera>),
+    >,
     mut bones: Query<
         (&mut Transform, Option<&BaseRotation>),
         (With<BoneName>, Without<PlayerCamera>),
     >,
-    mut cameras: Query<&mut Transform, With<PlayerCamera>>,
+    mut cameras: Query<(&mut Transform, &mut StepSmoothing), With<PlayerCamera>>,
     mut commands: Commands,
+    players: Query<
+        (
+            &Transform,
+            &Children,
+            &PlayerCrouch,
+            &PlayerHeight,
+            &PlayerStepHeight,
+        ),
+        (Without<BoneName>, Without<PlayerCamera>),
+    >,
+    remotes: Query<&RemotePlayer>,
+    time: Res<Time>,
 ) {
-    for (head, offset) in avatars.iter() {
+    let lerp_factor = (time.delta_seconds() * STEP_SMOOTHING_FACTOR).min(1.0);
+
+    for (head, offset, avatar_tr, parent) in avatars.iter() {
         let (mut head_tr, base) = bones.get_mut(head.0).expect("Avatar head bone not found");
 
         let Some(base) = base else {
@@ -59,8 +87,29 @@ pub(crate) fn rotate_avatar_head(
             continue;
         };
 
-        let mut camera_tr = cameras.single_mut();
-        camera_tr.translation = offset.0;
+        let Ok((player_tr, children, crouch, height, step)) = players.get(parent.get()) else {
+            continue;
+        };
+
+        if let Ok(remote) = remotes.get(parent.get()) {
+            head_tr.rotation = base.0 * remote.head_rotation;
+            continue;
+        }
+
+        let Some(camera) = children.iter().find(|child| cameras.contains(**child)) else {
+            continue;
+        };
+
+        let (mut camera_tr, mut smoothing) = cameras.get_mut(*camera).unwrap();
+        camera_tr.translation = avatar_tr.translation + offset.0;
+        camera_tr.translation.y -= height.0 - crouch.current_height(height.0);
+
+        let y = player_tr.translation.dot(*player_tr.up());
+        let smoothed = smoothing.0.unwrap_or(y);
+        let smoothed = (smoothed + (y - smoothed) * lerp_factor).clamp(y - step.0, y + step.0);
+        smoothing.0 = Some(smoothed);
+
+        camera_tr.translation.y += smoothed - y;
 
         let new_rot = base.0 * camera_tr.rotation;
         head_tr.rotation = new_rot;
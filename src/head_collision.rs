@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+/// Fades the view to black when the user's head enters static geometry,
+/// such as when leaning into a wall in XR.
+#[derive(Component, Clone, Debug)]
+pub struct HeadCollision {
+    /// Distance, in meters, over which the view fades to black.
+    pub fade_distance: f32,
+    /// If `true`, the tracking root is moved to push the head back out of geometry.
+    pub push_back: bool,
+    pub radius: f32,
+    /// Distance, in meters, the head can enter geometry before the view starts fading.
+    pub tolerance: f32,
+}
+
+impl Default for HeadCollision {
+    fn default() -> Self {
+        Self {
+            fade_distance: 0.1,
+            push_back: false,
+            radius: 0.1,
+            tolerance: 0.05,
+        }
+    }
+}
+
+#[cfg(feature = "xr")]
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn detect_head_collision_oxr(
+    bodies: Query<&avian3d::prelude::RigidBody>,
+    colliders: Query<&avian3d::prelude::ColliderParent>,
+    eye_offsets: Query<
+        (&crate::eye_offset::EyeOffset, &Transform),
+        Without<bevy_mod_xr::session::XrTrackingRoot>,
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    players: Query<
+        (
+            Entity,
+            &Transform,
+            &Children,
+            &HeadCollision,
+            &crate::vignette::PlayerOverlays,
+        ),
+        (
+            With<crate::player::PlayerBody>,
+            With<crate::input::XrUser>,
+            Without<bevy_mod_xr::session::XrTrackingRoot>,
+        ),
+    >,
+    sensors: Query<(), With<avian3d::prelude::Sensor>>,
+    spatial_query: avian3d::prelude::SpatialQuery,
+    views: Res<bevy_mod_openxr::resources::OxrViews>,
+    mut xr_root: Query<
+        &mut Transform,
+        (
+            With<bevy_mod_xr::session::XrTrackingRoot>,
+            Without<crate::player::PlayerBody>,
+        ),
+    >,
+) {
+    use avian3d::prelude::*;
+    use bevy_mod_openxr::helper_traits::ToVec3;
+
+    let Ok((entity, player_tr, children, settings, overlays)) = players.get_single() else {
+        return;
+    };
+
+    let Some((offset, avatar_tr)) = children.iter().find_map(|c| eye_offsets.get(*c).ok()) else {
+        return;
+    };
+
+    let mut fade = 0.0;
+
+    if let (Some(view), Ok(mut root_tr)) = (views.first(), xr_root.get_single_mut()) {
+        // Cast from the avatar's eyes, where the body keeps the head,
+        // to where the head actually is.
+        let eyes = player_tr.transform_point(avatar_tr.translation + offset.0);
+        let head = root_tr.transform_point(view.pose.position.to_vec3());
+        let to_head = head - eyes;
+
+        if let Ok(direction) = Dir3::new(to_head) {
+            let distance = to_head.length();
+
+            let hit = spatial_query
+                .shape_hits(
+                    &Collider::sphere(settings.radius),
+                    eyes,
+                    Quat::IDENTITY,
+                    direction,
+                    distance,
+                    8,
+                    true,
+                    SpatialQueryFilter::from_excluded_entities([entity]),
+                )
+                .into_iter()
+                .filter(|hit| {
+                    let body = colliders.get(hit.entity).map_or(hit.entity, |p| p.get());
+                    let is_static = bodies.get(body).map_or(true, |rb| rb.is_static());
+                    is_static && !sensors.contains(hit.entity)
+                })
+                .map(|hit| hit.time_of_impact)
+                .reduce(f32::min);
+
+            if let Some(time_of_impact) = hit {
+                let depth = distance - time_of_impact - settings.tolerance;
+
+                if depth > 0.0 {
+                    fade = (depth / settings.fade_distance.max(f32::EPSILON)).min(1.0);
+
+                    if settings.push_back {
+                        root_tr.translation -= direction * depth;
+                        fade = 0.0;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(material) = materials.get_mut(&overlays.fade) {
+        material.base_color.set_alpha(fade);
+    }
+}
@@ -2,6 +2,8 @@ use bevy::{prelude::*, transform::systems::propagate_transforms};
 
 mod systems;
 
+pub(crate) use systems::HumanoidIK;
+
 pub struct HumanoidIKPlugin;
 
 impl Plugin for HumanoidIKPlugin {
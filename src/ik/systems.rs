@@ -3,14 +3,20 @@ use bevy_mod_xr::session::{XrState, XrTrackingRoot};
 use bevy_vrm::BoneName;
 use bevy_xr_utils::tracking_utils::{XrTrackedLeftGrip, XrTrackedRightGrip};
 
-use crate::animation::load::AvatarAnimationClips;
+use crate::{
+    animation::load::AvatarAnimationClips,
+    input::XrUser,
+    player::{PlayerAvatar, PlayerBody},
+};
 
 use super::RunHumanoidIk;
 
 #[derive(Component)]
 pub struct HumanoidIK {
-    left_target: Entity,
-    right_target: Entity,
+    /// The avatar being posed.
+    pub(crate) avatar: Entity,
+    pub(crate) left_target: Entity,
+    pub(crate) right_target: Entity,
 }
 
 pub struct IKChain {
@@ -32,7 +38,10 @@ pub struct RestPose {
 
 pub fn setup_ik_system(
     mut commands: Commands,
+    avatars: Query<(), With<PlayerAvatar>>,
     bones: Query<(Entity, &BoneName)>,
+    children: Query<&Children>,
+    players: Query<&Children, (With<PlayerBody>, With<XrUser>)>,
     transforms: Query<&Transform>,
     mut has_run: Local<bool>,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -44,6 +53,16 @@ pub fn setup_ik_system(
         return;
     }
 
+    // Only the XR user's avatar is posed by IK.
+    let Some(avatar) = players.get_single().ok().and_then(|player_children| {
+        player_children
+            .iter()
+            .copied()
+            .find(|child| avatars.contains(*child))
+    }) else {
+        return;
+    };
+
     let mut hip = None;
 
     let mut left_shoulder = None;
@@ -51,7 +70,7 @@ pub fn setup_ik_system(
     let mut left_lower_arm = None;
     let mut left_hand = None;
 
-    for (entity, name) in bones.iter() {
+    for (entity, name) in bones.iter_many(children.iter_descendants(avatar)) {
         match name {
             BoneName::Hips => hip.replace(entity),
             BoneName::LeftShoulder => left_shoulder.replace(entity),
@@ -75,7 +94,7 @@ pub fn setup_ik_system(
     let mut right_lower_arm = None;
     let mut right_hand = None;
 
-    for (entity, name) in bones.iter() {
+    for (entity, name) in bones.iter_many(children.iter_descendants(avatar)) {
         match name {
             BoneName::RightShoulder => right_shoulder.replace(entity),
             BoneName::RightUpperArm => right_upper_arm.replace(entity),
@@ -163,6 +182,7 @@ pub fn setup_ik_system(
             },
         },
         HumanoidIK {
+            avatar,
             left_target,
             right_target,
         },
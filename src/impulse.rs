@@ -0,0 +1,120 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::{TnuaProximitySensor, TnuaToggle};
+
+use crate::{
+    movement::{PlayerInputState, PlayerMovementMode},
+    player::{float_height, PlayerBody, PlayerCrouch, PlayerHeight, PlayerStepHeight},
+};
+
+/// Launches a player, for example from an explosion, jump pad, or enemy hit.
+#[derive(Event, Debug, Clone)]
+pub struct PlayerImpulse {
+    pub player: Entity,
+    pub direction: Vec3,
+    /// Change in velocity, in meters per second.
+    /// Independent of the player's mass.
+    pub magnitude: f32,
+    /// Duration, in seconds, during which player input is ignored.
+    pub stun: Option<f32>,
+}
+
+/// Tracks an in-progress knockback.
+/// While active, the Tnua walk basis is disabled, so that it does not cancel out the impulse.
+#[derive(Component, Default)]
+pub(crate) struct PlayerKnockback {
+    started_at: Option<f32>,
+    stunned_until: f32,
+    basis_disabled: bool,
+}
+
+impl PlayerKnockback {
+    /// Ends the knockback. The walk basis is re-enabled by [apply_player_impulses].
+    pub(crate) fn cancel(&mut self) {
+        self.started_at = None;
+        self.stunned_until = 0.0;
+    }
+}
+
+/// Time after an impulse before landing ends the knockback,
+/// giving the player a chance to leave the ground.
+const LAUNCH_GRACE: f32 = 0.1;
+/// Distance above the float height at which the player counts as landed.
+const LANDED_TOLERANCE: f32 = 0.05;
+
+pub(crate) fn apply_player_impulses(
+    mut commands: Commands,
+    mut events: EventReader<PlayerImpulse>,
+    mut players: Query<
+        (
+            Entity,
+            &mut PlayerInputState,
+            &mut PlayerKnockback,
+            &mut LinearVelocity,
+            &PlayerMovementMode,
+            &PlayerCrouch,
+            &PlayerHeight,
+            &PlayerStepHeight,
+            &TnuaProximitySensor,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    for event in events.read() {
+        let Ok((_, _, mut knockback, mut linvel, ..)) = players.get_mut(event.player) else {
+            warn!("Impulse target {} is not a player", event.player);
+            continue;
+        };
+
+        linvel.0 += event.direction.normalize_or_zero() * event.magnitude;
+
+        knockback.started_at = Some(now);
+
+        if let Some(stun) = event.stun {
+            knockback.stunned_until = knockback.stunned_until.max(now + stun);
+        }
+    }
+
+    for (entity, mut input, mut knockback, _, mode, crouch, height, step, sensor) in
+        players.iter_mut()
+    {
+        let Some(started_at) = knockback.started_at else {
+            if knockback.basis_disabled {
+                knockback.basis_disabled = false;
+
+                if *mode == PlayerMovementMode::Walk {
+                    commands.entity(entity).insert(TnuaToggle::Enabled);
+                }
+            }
+            continue;
+        };
+
+        if *mode != PlayerMovementMode::Walk {
+            // Other movement modes do not use the walk basis.
+            knockback.cancel();
+            continue;
+        }
+
+        if !knockback.basis_disabled {
+            knockback.basis_disabled = true;
+            commands.entity(entity).insert(TnuaToggle::SenseOnly);
+        }
+
+        if now < knockback.stunned_until {
+            *input = PlayerInputState::default();
+            continue;
+        }
+
+        let landed = sensor.output.as_ref().is_some_and(|output| {
+            output.proximity
+                <= float_height(crouch.current_height(height.0), step.0) + LANDED_TOLERANCE
+        });
+
+        if landed && now - started_at > LAUNCH_GRACE {
+            knockback.cancel();
+        }
+    }
+}
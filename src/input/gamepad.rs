@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use crate::{movement::PlayerInputState, player::PlayerLook};
+
+use super::{mouse::PITCH_BOUND, PlayerInputSource};
+
+#[derive(Resource)]
+pub struct GamepadInputMap {
+    pub button_jump: GamepadButtonType,
+    pub button_sprint: GamepadButtonType,
+    pub button_crouch: GamepadButtonType,
+    pub button_dash: GamepadButtonType,
+    pub button_up: GamepadButtonType,
+    pub button_down: GamepadButtonType,
+    /// Look speed at full stick deflection, in radians per second.
+    pub look_speed: f32,
+}
+
+impl Default for GamepadInputMap {
+    fn default() -> Self {
+        Self {
+            button_jump: GamepadButtonType::South,
+            button_sprint: GamepadButtonType::LeftThumb,
+            button_crouch: GamepadButtonType::East,
+            button_dash: GamepadButtonType::West,
+            button_up: GamepadButtonType::RightTrigger,
+            button_down: GamepadButtonType::LeftTrigger,
+            look_speed: 3.0,
+        }
+    }
+}
+
+pub fn read_gamepad_input(
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    input_map: Res<GamepadInputMap>,
+    mut players: Query<(&PlayerInputSource, &mut PlayerInputState, &mut PlayerLook)>,
+    time: Res<Time>,
+) {
+    for (source, mut input, mut look) in players.iter_mut() {
+        let PlayerInputSource::Gamepad(gamepad) = *source else {
+            continue;
+        };
+
+        let axis = |axis_type| {
+            axes.get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        let pressed = |button_type| buttons.pressed(GamepadButton::new(gamepad, button_type));
+
+        input.forward = axis(GamepadAxisType::LeftStickY);
+        input.left = -axis(GamepadAxisType::LeftStickX);
+
+        let up = if pressed(input_map.button_up) {
+            1.0
+        } else {
+            0.0
+        };
+        let down = if pressed(input_map.button_down) {
+            -1.0
+        } else {
+            0.0
+        };
+        input.up = up + down;
+
+        input.jump = pressed(input_map.button_jump);
+        input.sprint = pressed(input_map.button_sprint);
+        input.crouch = pressed(input_map.button_crouch);
+        input.dash = pressed(input_map.button_dash);
+
+        let look_delta = Vec2::new(
+            -axis(GamepadAxisType::RightStickX),
+            axis(GamepadAxisType::RightStickY),
+        ) * input_map.look_speed
+            * time.delta_seconds();
+
+        if look_delta != Vec2::ZERO {
+            look.look_xy += look_delta;
+            look.look_xy.y = look.look_xy.y.clamp(-PITCH_BOUND, PITCH_BOUND);
+        }
+    }
+}
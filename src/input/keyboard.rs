@@ -2,6 +2,8 @@ use bevy::prelude::*;
 
 use crate::movement::PlayerInputState;
 
+use super::PlayerInputSource;
+
 #[derive(Resource)]
 pub struct InputMap {
     pub key_forward: KeyCode,
@@ -9,6 +11,11 @@ pub struct InputMap {
     pub key_left: KeyCode,
     pub key_right: KeyCode,
     pub key_jump: KeyCode,
+    pub key_sprint: KeyCode,
+    pub key_crouch: KeyCode,
+    pub key_dash: KeyCode,
+    pub key_up: KeyCode,
+    pub key_down: KeyCode,
 }
 
 impl Default for InputMap {
@@ -19,6 +26,11 @@ impl Default for InputMap {
             key_left: KeyCode::KeyA,
             key_right: KeyCode::KeyD,
             key_jump: KeyCode::Space,
+            key_sprint: KeyCode::ShiftLeft,
+            key_crouch: KeyCode::ControlLeft,
+            key_dash: KeyCode::KeyF,
+            key_up: KeyCode::KeyE,
+            key_down: KeyCode::KeyQ,
         }
     }
 }
@@ -26,7 +38,7 @@ impl Default for InputMap {
 pub fn read_keyboard_input(
     input_map: Res<InputMap>,
     keys: Res<ButtonInput<KeyCode>>,
-    mut player_state: Query<&mut PlayerInputState>,
+    mut player_state: Query<(&PlayerInputSource, &mut PlayerInputState)>,
     #[cfg(feature = "xr")]
     #[cfg(not(target_family = "wasm"))]
     views: Res<bevy_mod_openxr::resources::OxrViews>,
@@ -37,20 +49,32 @@ pub fn read_keyboard_input(
         return;
     }
 
-    for mut input in player_state.iter_mut() {
+    for (source, mut input) in player_state.iter_mut() {
+        if *source != PlayerInputSource::KeyboardMouse {
+            continue;
+        }
+
         let forward = keys.pressed(input_map.key_forward);
         let backward = keys.pressed(input_map.key_backward);
         let left = keys.pressed(input_map.key_left);
         let right = keys.pressed(input_map.key_right);
+        let up = keys.pressed(input_map.key_up);
+        let down = keys.pressed(input_map.key_down);
 
         let forward = if forward { 1.0 } else { 0.0 };
         let backward = if backward { -1.0 } else { 0.0 };
         let left = if left { 1.0 } else { 0.0 };
         let right = if right { -1.0 } else { 0.0 };
+        let up = if up { 1.0 } else { 0.0 };
+        let down = if down { -1.0 } else { 0.0 };
 
         input.forward = forward + backward;
         input.left = left + right;
+        input.up = up + down;
 
         input.jump = keys.pressed(input_map.key_jump);
+        input.sprint = keys.pressed(input_map.key_sprint);
+        input.crouch = keys.pressed(input_map.key_crouch);
+        input.dash = keys.pressed(input_map.key_dash);
     }
 }
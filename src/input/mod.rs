@@ -1,4 +1,54 @@
+use bevy::prelude::*;
+
+pub mod gamepad;
 pub mod keyboard;
 pub mod mouse;
 #[cfg(feature = "xr")]
 pub mod xr;
+
+/// Where a player's input comes from.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayerInputSource {
+    /// Keyboard and mouse, or the HMD and controllers when in XR.
+    #[default]
+    KeyboardMouse,
+    Gamepad(Gamepad),
+    /// No input, such as for players controlled by code.
+    None,
+}
+
+/// Marks the one player controlled by the XR user's HMD and controllers.
+/// Assigned automatically to the player using [PlayerInputSource::KeyboardMouse]
+/// with the lowest [Entity], and kept by that player until it switches input source.
+#[derive(Component, Debug)]
+pub struct XrUser;
+
+#[cfg(feature = "xr")]
+pub(crate) fn assign_xr_user(
+    mut commands: Commands,
+    players: Query<(Entity, &PlayerInputSource, Has<XrUser>), With<crate::player::PlayerBody>>,
+) {
+    let candidates = || {
+        players
+            .iter()
+            .filter(|(_, source, _)| **source == PlayerInputSource::KeyboardMouse)
+    };
+
+    // Keep the current XR user if it is still valid, so that it does not change
+    // when another player spawns.
+    let user = candidates()
+        .filter(|(.., xr_user)| *xr_user)
+        .map(|(entity, ..)| entity)
+        .min()
+        .or_else(|| candidates().map(|(entity, ..)| entity).min());
+
+    for (entity, _, xr_user) in players.iter() {
+        if Some(entity) == user {
+            if !xr_user {
+                commands.entity(entity).insert(XrUser);
+            }
+        } else if xr_user {
+            commands.entity(entity).remove::<XrUser>();
+        }
+    }
+}
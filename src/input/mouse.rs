@@ -2,17 +2,17 @@ use std::f32::consts::FRAC_PI_2;
 
 use bevy::{input::mouse::MouseMotion, prelude::*, window::CursorGrabMode};
 
-#[derive(Resource, Event, Debug, Default, Deref, DerefMut)]
-pub struct CameraLookEvent(pub Vec2);
+use crate::player::PlayerLook;
 
-const PITCH_BOUND: f32 = FRAC_PI_2 - 1E-3;
+use super::PlayerInputSource;
+
+pub(crate) const PITCH_BOUND: f32 = FRAC_PI_2 - 1E-3;
 const SENSITIVITY: f32 = 0.001;
 
 pub fn read_mouse_input(
     #[cfg(target_family = "wasm")] mut is_firefox: Local<Option<bool>>,
-    mut look_events: EventWriter<CameraLookEvent>,
-    mut look_xy: Local<Vec2>,
     mut mouse_motion_events: EventReader<MouseMotion>,
+    mut players: Query<(&PlayerInputSource, &mut PlayerLook)>,
     windows: Query<&Window>,
     #[cfg(feature = "xr")]
     #[cfg(not(target_family = "wasm"))]
@@ -58,8 +58,12 @@ pub fn read_mouse_input(
         }
     }
 
-    *look_xy += delta;
-    look_xy.y = look_xy.y.clamp(-PITCH_BOUND, PITCH_BOUND);
+    for (source, mut look) in players.iter_mut() {
+        if *source != PlayerInputSource::KeyboardMouse {
+            continue;
+        }
 
-    look_events.send(CameraLookEvent(*look_xy));
+        look.look_xy += delta;
+        look.look_xy.y = look.look_xy.y.clamp(-PITCH_BOUND, PITCH_BOUND);
+    }
 }
@@ -4,7 +4,18 @@ use bevy_xr_utils::xr_utils_actions::{
     ActiveSet, XRUtilsAction, XRUtilsActionSet, XRUtilsActionState, XRUtilsBinding,
 };
 
-use crate::movement::PlayerInputState;
+use crate::{
+    look::RotateYawEvent,
+    movement::PlayerInputState,
+    player::{XrLocomotion, XrTurn},
+};
+
+use super::XrUser;
+
+const PROFILES: [&str; 2] = [
+    "/interaction_profiles/valve/index_controller",
+    "/interaction_profiles/oculus/touch_controller",
+];
 
 pub fn setup_xr_actions(mut commands: Commands) {
     let set = commands
@@ -18,66 +29,204 @@ pub fn setup_xr_actions(mut commands: Commands) {
         ))
         .id();
 
+    let move_action = spawn_action(
+        &mut commands,
+        MoveAction,
+        "move",
+        ActionType::Vector,
+        &["/user/hand/left/input/thumbstick"],
+    );
+
+    let turn_action = spawn_action(
+        &mut commands,
+        TurnAction,
+        "turn",
+        ActionType::Vector,
+        &["/user/hand/right/input/thumbstick"],
+    );
+
+    let sprint_action = spawn_action(
+        &mut commands,
+        SprintAction,
+        "sprint",
+        ActionType::Bool,
+        &["/user/hand/left/input/thumbstick/click"],
+    );
+
+    let crouch_action = spawn_action(
+        &mut commands,
+        CrouchAction,
+        "crouch",
+        ActionType::Bool,
+        &["/user/hand/right/input/thumbstick/click"],
+    );
+
+    let dash_action = spawn_action(
+        &mut commands,
+        DashAction,
+        "dash",
+        ActionType::Bool,
+        &["/user/hand/right/input/a/click"],
+    );
+
+    commands.entity(set).push_children(&[
+        move_action,
+        turn_action,
+        sprint_action,
+        crouch_action,
+        dash_action,
+    ]);
+}
+
+/// Spawns an action, with a binding for each supported interaction profile.
+fn spawn_action(
+    commands: &mut Commands,
+    marker: impl Component,
+    name: &'static str,
+    action_type: ActionType,
+    bindings: &[&'static str],
+) -> Entity {
     let action = commands
         .spawn((
             XRUtilsAction {
-                action_name: "move".into(),
-                localized_name: "move_localized".into(),
-                action_type: ActionType::Vector,
+                action_name: name.into(),
+                localized_name: format!("{}_localized", name).into(),
+                action_type,
             },
-            MoveAction,
+            marker,
         ))
         .id();
 
-    let binding_left_index = commands
-        .spawn(XRUtilsBinding {
-            profile: "/interaction_profiles/valve/index_controller".into(),
-            binding: "/user/hand/left/input/thumbstick".into(),
-        })
-        .id();
-
-    let binding_left_touch = commands
-        .spawn(XRUtilsBinding {
-            profile: "/interaction_profiles/oculus/touch_controller".into(),
-            binding: "/user/hand/left/input/thumbstick".into(),
-        })
-        .id();
-
-    let binding_right_index = commands
-        .spawn(XRUtilsBinding {
-            profile: "/interaction_profiles/valve/index_controller".into(),
-            binding: "/user/hand/right/input/thumbstick".into(),
-        })
-        .id();
+    for profile in PROFILES {
+        for binding in bindings {
+            let binding = commands
+                .spawn(XRUtilsBinding {
+                    profile: profile.into(),
+                    binding: (*binding).into(),
+                })
+                .id();
 
-    let binding_right_touch = commands
-        .spawn(XRUtilsBinding {
-            profile: "/interaction_profiles/oculus/touch_controller".into(),
-            binding: "/user/hand/right/input/thumbstick".into(),
-        })
-        .id();
+            commands.entity(action).add_child(binding);
+        }
+    }
 
-    commands.entity(action).add_child(binding_left_index);
-    commands.entity(action).add_child(binding_left_touch);
-    commands.entity(action).add_child(binding_right_index);
-    commands.entity(action).add_child(binding_right_touch);
-    commands.entity(set).add_child(action);
+    action
 }
 
 #[derive(Component)]
 pub struct MoveAction;
 
+#[derive(Component)]
+pub struct TurnAction;
+
+#[derive(Component)]
+pub struct SprintAction;
+
+#[derive(Component)]
+pub struct CrouchAction;
+
+#[derive(Component)]
+pub struct DashAction;
+
 pub fn read_xr_input(
     move_action: Query<&XRUtilsActionState, With<MoveAction>>,
-    mut input_state: Query<&mut PlayerInputState>,
+    sprint_action: Query<&XRUtilsActionState, With<SprintAction>>,
+    crouch_action: Query<&XRUtilsActionState, With<CrouchAction>>,
+    dash_action: Query<&XRUtilsActionState, With<DashAction>>,
+    mut input_state: Query<(&mut PlayerInputState, &XrLocomotion), With<XrUser>>,
 ) {
+    let Ok((mut input, locomotion)) = input_state.get_single_mut() else {
+        return;
+    };
+
     for action_state in move_action.iter() {
-        for mut input in input_state.iter_mut() {
-            if let XRUtilsActionState::Vector(value) = action_state {
-                input.forward = value.current_state[1];
-                input.left = -value.current_state[0];
-            } else {
-                panic!("Invalid action state variant");
+        // In teleport mode, the thumbstick is used for aiming instead.
+        if *locomotion == XrLocomotion::Teleport {
+            input.forward = 0.0;
+            input.left = 0.0;
+            continue;
+        }
+
+        if let XRUtilsActionState::Vector(value) = action_state {
+            input.forward = value.current_state[1];
+            input.left = -value.current_state[0];
+        } else {
+            warn_once!("Invalid action state variant for the move action");
+        }
+    }
+
+    for action_state in sprint_action.iter() {
+        if let XRUtilsActionState::Bool(value) = action_state {
+            input.sprint = value.current_state;
+        } else {
+            warn_once!("Invalid action state variant for the sprint action");
+        }
+    }
+
+    for action_state in crouch_action.iter() {
+        if let XRUtilsActionState::Bool(value) = action_state {
+            input.crouch = value.current_state;
+        } else {
+            warn_once!("Invalid action state variant for the crouch action");
+        }
+    }
+
+    for action_state in dash_action.iter() {
+        if let XRUtilsActionState::Bool(value) = action_state {
+            input.dash = value.current_state;
+        } else {
+            warn_once!("Invalid action state variant for the dash action");
+        }
+    }
+}
+
+pub(crate) fn read_xr_turn_input(
+    mut last_turn: Local<Option<f32>>,
+    players: Query<(Entity, &XrTurn), With<XrUser>>,
+    time: Res<Time>,
+    turn_action: Query<&XRUtilsActionState, With<TurnAction>>,
+    mut yaw_events: EventWriter<RotateYawEvent>,
+) {
+    let Ok((entity, turn)) = players.get_single() else {
+        return;
+    };
+
+    let x = turn_action
+        .iter()
+        .find_map(|state| match state {
+            XRUtilsActionState::Vector(value) => Some(value.current_state[0]),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    match *turn {
+        XrTurn::Disabled => {}
+        XrTurn::Snap {
+            angle,
+            deadzone,
+            cooldown,
+        } => {
+            if x.abs() < deadzone {
+                *last_turn = None;
+                return;
+            }
+
+            let now = time.elapsed_seconds();
+
+            if last_turn.map_or(true, |last| now - last >= cooldown) {
+                *last_turn = Some(now);
+                yaw_events.send(RotateYawEvent {
+                    entity,
+                    yaw: -x.signum() * angle.to_radians(),
+                });
+            }
+        }
+        XrTurn::Smooth { speed } => {
+            if x != 0.0 {
+                yaw_events.send(RotateYawEvent {
+                    entity,
+                    yaw: -x * speed.to_radians() * time.delta_seconds(),
+                });
             }
         }
     }
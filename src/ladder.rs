@@ -0,0 +1,134 @@
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    movement::{PlayerInputState, PlayerMovementMode},
+    player::{PlayerBody, PlayerHeight},
+};
+
+/// A climbable ladder.
+/// Should be added to an entity with a [Sensor] collider.
+/// Players climb along the entity's local Y axis.
+#[derive(Component)]
+pub struct Ladder {
+    pub speed: f32,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self { speed: 2.0 }
+    }
+}
+
+/// Time after jumping off a ladder before the player can grab on again.
+const DETACH_COOLDOWN: f32 = 0.5;
+const JUMP_OFF_SPEED: f32 = 3.0;
+/// How strongly the player is pulled towards the ladder's axis while climbing.
+const LADDER_PULL: f32 = 4.0;
+
+pub(crate) fn update_ladder_mode(
+    mut detached: Local<HashMap<Entity, f32>>,
+    ladders: Query<&GlobalTransform, With<Ladder>>,
+    mut players: Query<
+        (
+            Entity,
+            &CollidingEntities,
+            &Position,
+            &PlayerInputState,
+            &PlayerHeight,
+            &mut PlayerMovementMode,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    detached.retain(|_, detached_at| now - *detached_at < DETACH_COOLDOWN);
+
+    for (entity, colliding, position, input, height, mut mode, mut linvel) in players.iter_mut() {
+        let current = *mode;
+
+        match current {
+            PlayerMovementMode::Walk => {
+                let Some(ladder) = colliding.iter().copied().find(|e| ladders.contains(*e)) else {
+                    continue;
+                };
+
+                if input.forward > 0.0 && !input.jump && !detached.contains_key(&entity) {
+                    *mode = PlayerMovementMode::Climb { ladder };
+                }
+            }
+            PlayerMovementMode::Climb { ladder } => {
+                let Ok(ladder_tr) = ladders.get(ladder) else {
+                    *mode = PlayerMovementMode::Walk;
+                    continue;
+                };
+
+                if !colliding.contains(&ladder) {
+                    *mode = PlayerMovementMode::Walk;
+                    continue;
+                }
+
+                if input.jump {
+                    let mut away = position.0 - ladder_tr.translation();
+                    away.y = 0.0;
+
+                    linvel.0 = (away.normalize_or_zero() + Vec3::Y) * JUMP_OFF_SPEED;
+
+                    detached.insert(entity, now);
+                    *mode = PlayerMovementMode::Walk;
+                    continue;
+                }
+
+                // Step off when climbing down onto the ground.
+                if input.forward < 0.0 {
+                    let ground = spatial_query.cast_ray(
+                        position.0,
+                        Dir3::NEG_Y,
+                        height.0 / 2.0 + 0.15,
+                        true,
+                        SpatialQueryFilter::from_excluded_entities([entity, ladder]),
+                    );
+
+                    if ground.is_some() {
+                        *mode = PlayerMovementMode::Walk;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn climb_player(
+    ladders: Query<(&Ladder, &GlobalTransform)>,
+    mut players: Query<
+        (
+            &Position,
+            &PlayerInputState,
+            &PlayerMovementMode,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+) {
+    for (position, input, mode, mut linvel) in players.iter_mut() {
+        let PlayerMovementMode::Climb { ladder } = mode else {
+            continue;
+        };
+
+        let Ok((ladder, ladder_tr)) = ladders.get(*ladder) else {
+            continue;
+        };
+
+        let axis = *ladder_tr.up();
+        let center = ladder_tr.translation();
+
+        let along = (position.0 - center).dot(axis);
+        let to_axis = center + axis * along - position.0;
+
+        linvel.0 = axis * input.forward * ladder.speed + to_axis * LADDER_PULL;
+    }
+}
@@ -1,20 +1,45 @@
+use avian3d::prelude::{PhysicsSchedule, PhysicsStepSet};
 use bevy::{asset::embedded_asset, prelude::*};
 use bevy_tnua::prelude::TnuaControllerPlugin;
 use bevy_tnua_avian3d::TnuaAvian3dPlugin;
 use bevy_vrm::VrmPlugins;
 
 pub mod animation;
+mod avatar_scale;
+pub mod calibration;
+mod crouch;
+pub mod dash;
 mod eye_offset;
-mod first_person;
+pub mod first_person;
+mod fly;
+pub mod gravity;
 mod head;
+pub mod head_collision;
 #[cfg(feature = "xr")]
 mod ik;
+pub mod impulse;
 pub mod input;
+pub mod ladder;
 mod look;
 pub mod movement;
+mod platform;
 pub mod player;
+pub mod pose;
+pub mod remote;
+pub mod render_layers;
+pub mod respawn;
+pub mod split_screen;
+pub mod swim;
+pub mod teleport;
 pub mod velocity;
+pub mod vignette;
+#[cfg(feature = "xr")]
+mod xr_teleport;
 
+/// Adds the player controller systems.
+///
+/// Render layers from [OVERLAY_LAYERS](render_layers::OVERLAY_LAYERS) onwards
+/// are reserved by the crate, see [render_layers].
 pub struct VrControllerPlugin;
 
 impl Plugin for VrControllerPlugin {
@@ -30,23 +55,66 @@ impl Plugin for VrControllerPlugin {
             TnuaControllerPlugin::default(),
             VrmPlugins,
         ))
+        .init_resource::<calibration::XrCalibration>()
+        .init_resource::<input::gamepad::GamepadInputMap>()
         .init_resource::<input::keyboard::InputMap>()
-        .add_event::<input::mouse::CameraLookEvent>()
+        .add_event::<calibration::CalibrateXrHeight>()
+        .add_event::<dash::PlayerDashEvent>()
+        .add_event::<impulse::PlayerImpulse>()
+        .add_event::<look::ResetLookEvent>()
+        .add_event::<look::RotateYawEvent>()
+        .add_event::<respawn::PlayerRespawned>()
+        .add_event::<respawn::RespawnPlayer>()
+        .add_event::<teleport::PlayerTeleported>()
+        .add_event::<teleport::TeleportPlayer>()
         .add_systems(
             Update,
             (
                 animation::init_animations,
                 animation::load::load_animation_nodes,
                 animation::weights::play_avatar_animations,
-                eye_offset::calc_eye_offset,
-                first_person::setup_first_person,
+                crouch::apply_crouch_offset,
+                (eye_offset::calc_eye_offset, avatar_scale::scale_avatars).chain(),
+                (
+                    first_person::setup_first_person,
+                    first_person::assign_player_render_layers,
+                    first_person::apply_avatar_render_layers,
+                    first_person::update_camera_render_layers,
+                )
+                    .chain(),
+                gravity::update_player_up.before(look::apply_camera_look),
+                #[cfg(feature = "xr")]
+                input::xr::read_xr_turn_input.before(look::apply_camera_look),
                 head::set_avatar_head,
                 look::grab_mouse,
+                movement::apply_movement_mode,
+                platform::ride_platforms.before(look::apply_camera_look),
                 #[cfg(feature = "xr")]
                 player::set_xr_render_layers,
+                (
+                    respawn::update_checkpoints,
+                    movement::void_teleport,
+                    respawn::apply_kill_volumes,
+                    respawn::respawn_players,
+                    teleport::teleport_players,
+                )
+                    .chain()
+                    .before(look::apply_camera_look),
+                split_screen::update_split_screen_viewports,
                 velocity::calc_average_velocity,
                 (
-                    input::mouse::read_mouse_input,
+                    vignette::setup_overlays,
+                    vignette::fit_overlays,
+                    vignette::update_vignette,
+                )
+                    .after(velocity::calc_average_velocity),
+                #[cfg(feature = "xr")]
+                xr_teleport::aim_xr_teleport.before(teleport::teleport_players),
+                (
+                    (
+                        input::gamepad::read_gamepad_input,
+                        input::mouse::read_mouse_input,
+                    ),
                     look::apply_camera_look,
                     (
                         head::rotate_avatar_head,
@@ -55,13 +123,37 @@ impl Plugin for VrControllerPlugin {
                                 input::keyboard::read_keyboard_input,
                                 #[cfg(feature = "xr")]
                                 input::xr::read_xr_input,
+                                (
+                                    remote::interpolate_remote_players,
+                                    remote::apply_remote_players,
+                                )
+                                    .chain()
+                                    .before(velocity::calc_average_velocity),
                             ),
-                            movement::void_teleport,
+                            impulse::apply_player_impulses,
+                            crouch::crouch_player,
+                            swim::update_swim_mode,
+                            ladder::update_ladder_mode,
+                            fly::fly_player,
+                            swim::swim_player,
+                            ladder::climb_player,
+                            movement::slide_player,
+                            dash::dash_player,
                             movement::move_player,
                             #[cfg(feature = "xr")]
                             #[cfg(not(target_family = "wasm"))]
+                            movement::move_body_room_scale_oxr,
+                            #[cfg(feature = "xr")]
+                            #[cfg(not(target_family = "wasm"))]
+                            calibration::calibrate_xr_height,
+                            #[cfg(feature = "xr")]
+                            #[cfg(not(target_family = "wasm"))]
                             movement::move_xr_root_oxr,
-                        ),
+                            #[cfg(feature = "xr")]
+                            #[cfg(not(target_family = "wasm"))]
+                            head_collision::detect_head_collision_oxr,
+                        )
+                            .chain(),
                     )
                         .chain(),
                 )
@@ -69,11 +161,32 @@ impl Plugin for VrControllerPlugin {
             ),
         );
 
+        app.add_systems(
+            PhysicsSchedule,
+            (
+                gravity::apply_player_gravity.in_set(PhysicsStepSet::First),
+                respawn::clear_respawning.in_set(PhysicsStepSet::Last),
+            ),
+        );
+
+        app.add_systems(
+            PostUpdate,
+            (pose::capture_poses, pose::apply_poses)
+                .after(bevy::animation::Animation)
+                .before(TransformSystem::TransformPropagate),
+        );
+
+        #[cfg(feature = "xr")]
+        app.add_systems(PreUpdate, input::assign_xr_user);
+
         #[cfg(feature = "xr")]
         app.add_systems(
             Startup,
-            input::xr::setup_xr_actions
-                .before(bevy_xr_utils::xr_utils_actions::XRUtilsActionSystemSet::CreateEvents),
+            (
+                input::xr::setup_xr_actions
+                    .before(bevy_xr_utils::xr_utils_actions::XRUtilsActionSystemSet::CreateEvents),
+                xr_teleport::setup_xr_teleport,
+            ),
         );
 
         embedded_asset!(app, "animation/default-animations.glb");
@@ -1,31 +1,90 @@
-use bevy::{prelude::*, window::CursorGrabMode, window::Window};
+use bevy::{prelude::*, utils::HashMap, window::CursorGrabMode, window::Window};
 
 use crate::{
-    input::mouse::CameraLookEvent,
-    player::{CameraFreeLook, PlayerBody, PlayerCamera},
+    gravity::PlayerUp,
+    input::XrUser,
+    player::{CameraFreeLook, PlayerBody, PlayerCamera, PlayerLook},
 };
 
 const CAM_LERP_FACTOR: f32 = 30.0;
 
+/// Rotates a player's yaw by the given angle, in radians.
+#[derive(Event)]
+pub(crate) struct RotateYawEvent {
+    pub entity: Entity,
+    pub yaw: f32,
+}
+
+/// Snaps a player's rotation to its current look targets, skipping smoothing.
+/// If `yaw` is set, the look targets are first reset to face that angle, in radians.
+#[derive(Event)]
+pub(crate) struct ResetLookEvent {
+    pub entity: Entity,
+    pub yaw: Option<f32>,
+}
+
 pub fn apply_camera_look(
     mut cameras: Query<
         (&mut Transform, &CameraFreeLook),
         (With<PlayerCamera>, Without<PlayerBody>),
     >,
-    mut free_yaw: Local<Option<Quat>>,
-    mut look_events: EventReader<CameraLookEvent>,
-    mut players: Query<(&mut Transform, &Children), (With<PlayerBody>, Without<Camera>)>,
-    mut target_pitch_roll: Local<Quat>,
-    mut target_yaw: Local<Quat>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Children,
+            &mut PlayerLook,
+            Has<XrUser>,
+            &PlayerUp,
+        ),
+        (With<PlayerBody>, Without<Camera>),
+    >,
+    mut reset_events: EventReader<ResetLookEvent>,
     time: Res<Time>,
+    mut yaw_events: EventReader<RotateYawEvent>,
     #[cfg(feature = "xr")]
     #[cfg(not(target_family = "wasm"))]
     views: Res<bevy_mod_openxr::resources::OxrViews>,
+    #[cfg(feature = "xr")]
+    #[cfg(not(target_family = "wasm"))]
+    mut xr_root: Query<
+        &mut Transform,
+        (
+            With<bevy_mod_xr::session::XrTrackingRoot>,
+            Without<PlayerCamera>,
+            Without<PlayerBody>,
+        ),
+    >,
 ) {
+    let mut resets = HashMap::<Entity, Option<f32>>::default();
+
+    for event in reset_events.read() {
+        let reset = resets.entry(event.entity).or_default();
+
+        if event.yaw.is_some() {
+            *reset = event.yaw;
+        }
+    }
+
+    let mut yaws = HashMap::<Entity, f32>::default();
+
+    for event in yaw_events.read() {
+        *yaws.entry(event.entity).or_default() += event.yaw;
+    }
+
+    #[allow(unused_mut)]
+    let mut xr_targets: Option<(Entity, Quat, Quat)> = None;
+
     #[cfg(feature = "xr")]
     #[cfg(not(target_family = "wasm"))]
-    if let Some(view) = views.first() {
-        use bevy_mod_openxr::helper_traits::ToQuat;
+    if let (Some(view), Some(xr_player)) = (
+        views.first(),
+        players
+            .iter()
+            .find(|(.., xr_user, _)| *xr_user)
+            .map(|(entity, ..)| entity),
+    ) {
+        use bevy_mod_openxr::helper_traits::{ToQuat, ToVec3};
 
         let rotation = view.pose.orientation.to_quat();
 
@@ -34,39 +93,79 @@ pub fn apply_camera_look(
 
         yaw.x = 0.0;
         yaw.z = 0.0;
-        *target_yaw = yaw.normalize();
+        let yaw = yaw.normalize();
 
         pitch_roll.y = 0.0;
-        *target_pitch_roll = pitch_roll.normalize();
+        let pitch_roll = pitch_roll.normalize();
 
-        look_events.clear()
-    }
+        // In XR, yaw is applied by rotating the tracking root.
+        let mut root_rotation = Quat::IDENTITY;
+
+        if let Ok(mut root_tr) = xr_root.get_single_mut() {
+            // Rotate around the HMD, so the user isn't displaced.
+            let hmd = root_tr.transform_point(view.pose.position.to_vec3());
+            let delta = yaws.get(&xr_player).copied().unwrap_or_default();
+            root_tr.rotate_around(hmd, Quat::from_rotation_y(delta));
+
+            if let Some(Some(reset_yaw)) = resets.get(&xr_player) {
+                let delta = Quat::from_rotation_y(*reset_yaw) * (root_tr.rotation * yaw).inverse();
+                root_tr.rotation = (delta * root_tr.rotation).normalize();
+            }
+
+            root_rotation = root_tr.rotation;
+        }
 
-    for look in look_events.read() {
-        *target_yaw = Quat::from_rotation_y(look.x);
-        *target_pitch_roll = Quat::from_rotation_x(look.y);
+        xr_targets = Some((xr_player, root_rotation * yaw, pitch_roll));
     }
 
-    let lerp_factor = time.delta_seconds() * CAM_LERP_FACTOR;
+    for (entity, mut player_tr, children, mut look, _, up) in players.iter_mut() {
+        let reset = resets.get(&entity);
+
+        if let Some((_, target_yaw, target_pitch_roll)) =
+            xr_targets.filter(|(xr_player, ..)| *xr_player == entity)
+        {
+            look.target_yaw = target_yaw;
+            look.target_pitch_roll = target_pitch_roll;
+        } else {
+            if let Some(yaw) = yaws.get(&entity) {
+                look.yaw_offset = Quat::from_rotation_y(*yaw) * look.yaw_offset;
+            }
+
+            if let Some(Some(reset_yaw)) = reset {
+                let current = look.yaw_offset * Quat::from_rotation_y(look.look_xy.x);
+                look.yaw_offset =
+                    Quat::from_rotation_y(*reset_yaw) * current.inverse() * look.yaw_offset;
+            }
+
+            look.target_yaw = look.yaw_offset * Quat::from_rotation_y(look.look_xy.x);
+            look.target_pitch_roll = Quat::from_rotation_x(look.look_xy.y);
+        }
+
+        let lerp_factor = if reset.is_some() {
+            look.free_yaw = None;
+            1.0
+        } else {
+            time.delta_seconds() * CAM_LERP_FACTOR
+        };
 
-    for (mut player_tr, children) in players.iter_mut() {
         for child in children.iter() {
             if let Ok((mut camera_tr, free)) = cameras.get_mut(*child) {
                 let target = if free.0 {
-                    if let Some(free_yaw) = *free_yaw {
-                        (*target_yaw * free_yaw.inverse()) * *target_pitch_roll
+                    if let Some(free_yaw) = look.free_yaw {
+                        (look.target_yaw * free_yaw.inverse()) * look.target_pitch_roll
                     } else {
-                        *free_yaw = Some(*target_yaw);
-                        *target_pitch_roll
+                        look.free_yaw = Some(look.target_yaw);
+                        look.target_pitch_roll
                     }
                 } else {
-                    player_tr.rotation = player_tr.rotation.lerp(*target_yaw, lerp_factor);
+                    player_tr.rotation =
+                        player_tr.rotation.lerp(up.0 * look.target_yaw, lerp_factor);
 
-                    if free_yaw.is_some() {
-                        *free_yaw = None;
+                    if look.free_yaw.is_some() {
+                        look.free_yaw = None;
                     }
 
-                    *target_pitch_roll
+                    look.target_pitch_roll
                 };
 
                 camera_tr.rotation = camera_tr.rotation.lerp(target, lerp_factor);
@@ -1,16 +1,72 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use bevy_tnua::prelude::*;
+use bevy_tnua::{prelude::*, TnuaProximitySensor, TnuaToggle};
 
-use crate::player::{
-    PlayerBody, PlayerHeight, PlayerJumpHeight, PlayerSpawn, PlayerSpeed, VoidTeleport,
+use crate::{
+    gravity::{height_above, PlayerGravity, PlayerUp},
+    player::{
+        float_height, PlayerBody, PlayerCrouch, PlayerHeight, PlayerJumpHeight, PlayerJumpSettings,
+        PlayerSlope, PlayerSpeed, PlayerSprintSpeed, PlayerStepHeight, VoidTeleport,
+    },
+    respawn::{RespawnPlayer, Respawning},
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Clone, Copy, Debug, Default)]
 pub struct PlayerInputState {
     pub forward: f32,
     pub left: f32,
     pub jump: bool,
+    pub sprint: bool,
+    pub crouch: bool,
+    pub dash: bool,
+    /// Vertical movement, used while flying.
+    pub up: f32,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayerMovementMode {
+    #[default]
+    Walk,
+    /// Free 3D flight, relative to the camera.
+    /// If `noclip` is `true`, the player can fly through colliders.
+    Fly { noclip: bool },
+    /// Buoyant 3D swimming, set while the player is submerged in a
+    /// [WaterVolume](crate::swim::WaterVolume).
+    Swim { water: Entity },
+    /// Climbing along a [Ladder](crate::ladder::Ladder).
+    Climb { ladder: Entity },
+}
+
+/// Toggles gravity and the Tnua controller, depending on the movement mode.
+pub(crate) fn apply_movement_mode(
+    mut commands: Commands,
+    players: Query<(Entity, &PlayerMovementMode), Changed<PlayerMovementMode>>,
+) {
+    for (entity, mode) in players.iter() {
+        let mut entity = commands.entity(entity);
+
+        match mode {
+            PlayerMovementMode::Walk => {
+                entity
+                    .insert((GravityScale(1.0), TnuaToggle::Enabled))
+                    .remove::<Sensor>();
+            }
+            PlayerMovementMode::Fly { noclip } => {
+                entity.insert((GravityScale(0.0), TnuaToggle::Disabled));
+
+                if *noclip {
+                    entity.insert(Sensor);
+                } else {
+                    entity.remove::<Sensor>();
+                }
+            }
+            PlayerMovementMode::Swim { .. } | PlayerMovementMode::Climb { .. } => {
+                entity
+                    .insert((GravityScale(0.0), TnuaToggle::Disabled))
+                    .remove::<Sensor>();
+            }
+        }
+    }
 }
 
 pub fn move_player(
@@ -19,9 +75,16 @@ pub fn move_player(
         (
             &Transform,
             &mut PlayerInputState,
+            &PlayerMovementMode,
             &PlayerHeight,
+            &PlayerCrouch,
             &PlayerSpeed,
+            &PlayerSprintSpeed,
             &PlayerJumpHeight,
+            &mut PlayerJumpSettings,
+            &PlayerSlope,
+            &PlayerStepHeight,
+            &PlayerUp,
             &mut TnuaController,
         ),
         With<PlayerBody>,
@@ -30,7 +93,27 @@ pub fn move_player(
 ) {
     debug_assert!(*last_time >= 0.0);
 
-    for (transform, mut input, height, speed, jump_height, mut controller) in players.iter_mut() {
+    for (
+        transform,
+        mut input,
+        mode,
+        height,
+        crouch,
+        speed,
+        sprint_speed,
+        jump_height,
+        mut jump,
+        slope,
+        step,
+        up,
+        mut controller,
+    ) in players.iter_mut()
+    {
+        if *mode != PlayerMovementMode::Walk {
+            *input = PlayerInputState::default();
+            continue;
+        }
+
         let dir_forward = transform.rotation.mul_vec3(Vec3::NEG_Z);
         let dir_left = transform.rotation.mul_vec3(Vec3::NEG_X);
 
@@ -39,19 +122,58 @@ pub fn move_player(
         move_direction += dir_forward * input.forward;
         move_direction += dir_left * input.left;
 
-        let desired_velocity = move_direction.normalize_or_zero() * speed.0;
+        let speed = if input.sprint {
+            sprint_speed.0
+        } else {
+            speed.0
+        };
+
+        let desired_velocity = move_direction.normalize_or_zero() * speed;
+
+        let airborne = controller.is_airborne().unwrap_or(false);
+
+        if !airborne {
+            jump.air_jumps_used = 0;
+        }
 
         if input.jump {
-            controller.action(TnuaBuiltinJump {
+            if !jump.pressed {
+                jump.air_jump = airborne && jump.air_jumps_used < jump.air_jumps;
+
+                if jump.air_jump {
+                    jump.air_jumps_used += 1;
+                }
+            }
+
+            let mut action = TnuaBuiltinJump {
+                allow_in_air: jump.air_jump,
                 height: jump_height.0,
+                input_buffer_time: jump.input_buffer_time,
                 ..default()
-            });
+            };
+
+            if !jump.variable_height {
+                action.shorten_extra_gravity = 0.0;
+            }
+
+            controller.action(action);
         }
 
+        jump.pressed = input.jump;
+
+        let height = if crouch.crouching {
+            crouch.height
+        } else {
+            height.0
+        };
+
+        // Actions, such as jumping and dashing, use the up direction of the basis.
         controller.basis(TnuaBuiltinWalk {
-            coyote_time: 0.2,
+            coyote_time: jump.coyote_time,
             desired_velocity,
-            float_height: (height.0 / 2.0) + 0.1,
+            float_height: float_height(height, step.0),
+            max_slope: slope.max_slope,
+            up: Dir3::new_unchecked(up.0 * Vec3::Y),
             ..default()
         });
 
@@ -61,17 +183,57 @@ pub fn move_player(
     *last_time = time.elapsed_seconds();
 }
 
+/// Pulls players down slopes steeper than their [PlayerSlope::max_slope].
+pub fn slide_player(
+    mut players: Query<
+        (
+            &Transform,
+            &PlayerMovementMode,
+            &PlayerSlope,
+            &TnuaProximitySensor,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+) {
+    for (transform, mode, slope, sensor, mut linvel) in players.iter_mut() {
+        if *mode != PlayerMovementMode::Walk {
+            continue;
+        }
+
+        let Some(output) = &sensor.output else {
+            continue;
+        };
+
+        let normal: Vec3 = output.normal.into();
+        let up = *transform.up();
+
+        if normal.angle_between(up) <= slope.max_slope {
+            continue;
+        }
+
+        let downhill = (-up).reject_from(normal).normalize_or_zero();
+        linvel.0 += downhill * slope.slide_acceleration * time.delta_seconds();
+    }
+}
+
 #[cfg(feature = "xr")]
 #[cfg(not(target_family = "wasm"))]
 pub fn move_xr_root_oxr(
+    calibration: Res<crate::calibration::XrCalibration>,
     player: Query<
         (&Transform, &Children),
         (
             With<PlayerBody>,
+            With<crate::input::XrUser>,
             Without<bevy_mod_xr::session::XrTrackingRoot>,
         ),
     >,
-    eye_offset: Query<&crate::eye_offset::EyeOffset>,
+    eye_offset: Query<
+        (&crate::eye_offset::EyeOffset, &Transform),
+        Without<bevy_mod_xr::session::XrTrackingRoot>,
+    >,
     mut xr_root: Query<
         &mut Transform,
         (
@@ -95,36 +257,104 @@ pub fn move_xr_root_oxr(
         return;
     };
 
-    let Some(offset) = children.iter().find_map(|c| eye_offset.get(*c).ok()) else {
+    let Some((offset, avatar_tr)) = children.iter().find_map(|c| eye_offset.get(*c).ok()) else {
         return;
     };
 
-    root_tr.translation = player_tr.translation;
-    root_tr.translation += offset.0;
-    root_tr.translation -= view.pose.position.to_vec3();
+    root_tr.translation = player_tr.transform_point(avatar_tr.translation + offset.0);
+    let mut hmd = view.pose.position.to_vec3();
+    hmd.y = calibration.reference_height(hmd.y);
+
+    root_tr.translation -= root_tr.rotation * hmd;
+}
+
+/// Moves the player body along with the user's physical movement in their play space.
+/// If the body is blocked, [move_xr_root_oxr] moves the tracking root to compensate.
+#[cfg(feature = "xr")]
+#[cfg(not(target_family = "wasm"))]
+pub fn move_body_room_scale_oxr(
+    mut prev_hmd: Local<Option<Vec3>>,
+    mut player: Query<
+        (Entity, &mut Transform, &Collider, &PlayerMovementMode),
+        (
+            With<PlayerBody>,
+            With<crate::input::XrUser>,
+            Without<bevy_mod_xr::session::XrTrackingRoot>,
+        ),
+    >,
+    spatial_query: SpatialQuery,
+    xr_root: Query<
+        &Transform,
+        (
+            With<bevy_mod_xr::session::XrTrackingRoot>,
+            Without<PlayerBody>,
+        ),
+    >,
+    views: Res<bevy_mod_openxr::resources::OxrViews>,
+) {
+    use bevy_mod_openxr::helper_traits::ToVec3;
+
+    /// Gap kept between the body and obstacles.
+    const SKIN: f32 = 0.01;
+
+    let Some(view) = views.first() else {
+        *prev_hmd = None;
+        return;
+    };
+
+    let hmd = view.pose.position.to_vec3();
+    let Some(prev) = prev_hmd.replace(hmd) else {
+        return;
+    };
+
+    let Ok(root_tr) = xr_root.get_single() else {
+        return;
+    };
+
+    let Ok((entity, mut player_tr, collider, mode)) = player.get_single_mut() else {
+        return;
+    };
+
+    let up = *player_tr.up();
+    let displacement = root_tr.rotation * (hmd - prev);
+    let displacement = displacement - up * displacement.dot(up);
+
+    let Ok(direction) = Dir3::new(displacement) else {
+        return;
+    };
+
+    let distance = displacement.length();
+
+    let allowed = if *mode == (PlayerMovementMode::Fly { noclip: true }) {
+        distance
+    } else {
+        spatial_query
+            .cast_shape(
+                collider,
+                player_tr.translation,
+                player_tr.rotation,
+                direction,
+                distance,
+                true,
+                SpatialQueryFilter::from_excluded_entities([entity]),
+            )
+            .map_or(distance, |hit| (hit.time_of_impact - SKIN).max(0.0))
+    };
+
+    player_tr.translation += direction * allowed;
 }
 
 pub fn void_teleport(
-    mut players: Query<(
-        &PlayerSpawn,
-        &VoidTeleport,
-        &mut Transform,
-        &mut LinearVelocity,
-        &mut AngularVelocity,
-    )>,
+    mut events: EventWriter<RespawnPlayer>,
+    players: Query<
+        (Entity, &VoidTeleport, &Transform, Option<&PlayerGravity>),
+        Without<Respawning>,
+    >,
 ) {
-    for (spawn, void_level, mut transform, mut linvel, mut angvel) in players.iter_mut() {
-        if transform.translation.y < void_level.0 {
-            debug!("Player fell into void! Teleporting player to spawn...");
-            transform.translation = spawn.0;
-            angvel.x = 0.0;
-            angvel.y = 0.0;
-            angvel.z = 0.0;
-            linvel.x = 0.0;
-            linvel.y = 0.0;
-            linvel.z = 0.0;
-
-            // TODO: Reset camera rotation
+    for (entity, void_level, transform, gravity) in players.iter() {
+        if height_above(gravity, transform.translation) < void_level.0 {
+            debug!("Player fell into void! Respawning player...");
+            events.send(RespawnPlayer { entity });
         }
     }
 }
@@ -0,0 +1,40 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::{prelude::*, TnuaProximitySensor};
+
+use crate::{gravity::PlayerUp, look::RotateYawEvent, player::PlayerBody};
+
+/// Rotates players standing on a rotating platform along with it,
+/// around the player's [PlayerUp] axis.
+///
+/// Only the facing is handled here. The sensor reports the ground's velocity at the
+/// contact point, including the part caused by its rotation, and the walk basis
+/// matches it, so it must not be added to the desired velocity again.
+/// Ground marked with `TnuaNotPlatform` does not carry players.
+pub(crate) fn ride_platforms(
+    platforms: Query<&AngularVelocity, Without<PlayerBody>>,
+    players: Query<(Entity, &PlayerUp, &TnuaController, &TnuaProximitySensor), With<PlayerBody>>,
+    time: Res<Time>,
+    mut yaw_events: EventWriter<RotateYawEvent>,
+) {
+    for (entity, up, controller, sensor) in players.iter() {
+        if controller.is_airborne().unwrap_or(true) {
+            continue;
+        }
+
+        let Some(ground) = sensor.output.as_ref().map(|output| output.entity) else {
+            continue;
+        };
+
+        let Ok(angvel) = platforms.get(ground) else {
+            continue;
+        };
+
+        // Spin around any other axis tilts the player, which is left to gravity.
+        let yaw = angvel.0.dot(up.0 * Vec3::Y) * time.delta_seconds();
+
+        if yaw != 0.0 {
+            yaw_events.send(RotateYawEvent { entity, yaw });
+        }
+    }
+}
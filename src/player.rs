@@ -1,3 +1,5 @@
+use std::f32::consts::FRAC_PI_4;
+
 use avian3d::prelude::*;
 use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_tnua::prelude::TnuaControllerBundle;
@@ -9,32 +11,104 @@ use bevy_vrm::{
 };
 
 use crate::{
-    animation::load::AvatarAnimationClips, first_person::FirstPerson, movement::PlayerInputState,
+    animation::load::AvatarAnimationClips,
+    first_person::FirstPerson,
+    gravity::PlayerUp,
+    head::StepSmoothing,
+    head_collision::HeadCollision,
+    impulse::PlayerKnockback,
+    input::PlayerInputSource,
+    movement::{PlayerInputState, PlayerMovementMode},
+    remote::RemotePlayer,
+    respawn::RespawnPoint,
+    split_screen::SplitScreen,
     velocity::AverageVelocity,
+    vignette::ComfortVignette,
 };
 
 pub struct PlayerSettings {
+    /// Number of extra jumps the player can perform while in the air.
+    pub air_jumps: u32,
     pub animations: Option<AvatarAnimationClips>,
+    /// How the avatar and the player are sized to match each other.
+    pub avatar_scaling: AvatarScaling,
+    /// How long, in seconds, the player can still jump after walking off a ledge.
+    pub coyote_time: f32,
+    /// Height of the player while crouched.
+    /// Below `max_step_height + width`, the collider shrinks to a sphere.
+    pub crouch_height: f32,
+    /// If `true`, the crouch input toggles crouching instead of needing to be held.
+    pub crouch_toggle: bool,
+    /// Cooldown, in seconds, between dashes.
+    pub dash_cooldown: f32,
+    pub dash_distance: f32,
+    /// Duration of a dash, in seconds.
+    pub dash_duration: f32,
+    /// If `true`, the player can dash while in the air.
+    pub dash_in_air: bool,
+    /// Fades the view when the user's head enters geometry in XR, disabled if `None`.
+    pub head_collision: Option<HeadCollision>,
     pub height: f32,
+    pub input_source: PlayerInputSource,
+    /// How long, in seconds, a jump input is remembered before landing.
+    pub jump_buffer_time: f32,
     pub jump_height: f32,
+    /// Maximum angle, in radians, of a slope the player can walk up.
+    pub max_slope: f32,
+    /// Maximum height of a step the player can walk up, such as a stair.
+    pub max_step_height: f32,
+    /// Acceleration pulling the player down slopes steeper than `max_slope`.
+    pub slope_slide_acceleration: f32,
     pub spawn: Vec3,
     pub speed: f32,
+    /// Renders the player's camera to a slice of the window, for local split-screen.
+    pub split_screen: Option<SplitScreen>,
+    pub sprint_speed: f32,
+    /// If `true`, releasing the jump input early shortens the jump.
+    pub variable_jump_height: bool,
+    /// Comfort vignette shown during artificial movement, disabled if `None`.
+    pub vignette: Option<ComfortVignette>,
     pub void_level: Option<f32>,
     pub vrm: Option<Handle<Vrm>>,
     pub width: f32,
+    /// Locomotion method used in XR.
+    pub xr_locomotion: XrLocomotion,
+    /// Turning method used in XR, using the right thumbstick.
+    pub xr_turn: XrTurn,
 }
 
 impl Default for PlayerSettings {
     fn default() -> Self {
         Self {
+            air_jumps: 0,
             animations: None,
+            avatar_scaling: AvatarScaling::default(),
+            coyote_time: 0.2,
+            crouch_height: 1.0,
+            crouch_toggle: false,
+            dash_cooldown: 1.0,
+            dash_distance: 3.0,
+            dash_duration: 0.2,
+            dash_in_air: true,
+            head_collision: None,
             height: 1.6,
+            input_source: PlayerInputSource::default(),
+            jump_buffer_time: 0.2,
             jump_height: 1.0,
+            max_slope: FRAC_PI_4,
+            max_step_height: 0.2,
+            slope_slide_acceleration: 8.0,
             spawn: Vec3::default(),
             speed: 4.0,
+            split_screen: None,
+            sprint_speed: 7.0,
+            variable_jump_height: true,
+            vignette: None,
             void_level: None,
             vrm: None,
             width: 0.4,
+            xr_locomotion: XrLocomotion::default(),
+            xr_turn: XrTurn::default(),
         }
     }
 }
@@ -42,14 +116,59 @@ impl Default for PlayerSettings {
 impl PlayerSettings {
     pub fn spawn(&self, commands: &mut Commands) -> SpawnedPlayer {
         let mut body = commands.spawn((
-            Collider::capsule(self.width / 2.0, self.height - self.width),
+            CollidingEntities::default(),
+            player_collider(self.height, self.width, self.max_step_height),
             LockedAxes::ROTATION_LOCKED,
             PlayerBody,
-            PlayerHeight(self.height),
-            PlayerInputState::default(),
-            PlayerJumpHeight(self.jump_height),
-            PlayerSpawn(self.spawn),
-            PlayerSpeed(self.speed),
+            (
+                self.avatar_scaling,
+                PlayerCrouch {
+                    height: self.crouch_height,
+                    toggle: self.crouch_toggle,
+                    crouching: false,
+                    amount: 0.0,
+                    pressed: false,
+                },
+                PlayerDash {
+                    allow_in_air: self.dash_in_air,
+                    cooldown: self.dash_cooldown,
+                    distance: self.dash_distance,
+                    duration: self.dash_duration,
+                    displacement: Vec3::ZERO,
+                    last_dash: None,
+                },
+                PlayerHeight(self.height),
+                PlayerJumpHeight(self.jump_height),
+                PlayerJumpSettings {
+                    air_jumps: self.air_jumps,
+                    coyote_time: self.coyote_time,
+                    input_buffer_time: self.jump_buffer_time,
+                    variable_height: self.variable_jump_height,
+                    air_jump: false,
+                    air_jumps_used: 0,
+                    pressed: false,
+                },
+                PlayerSlope {
+                    max_slope: self.max_slope,
+                    slide_acceleration: self.slope_slide_acceleration,
+                },
+                PlayerSpawn(self.spawn),
+                PlayerSpeed(self.speed),
+                PlayerSprintSpeed(self.sprint_speed),
+                PlayerStepHeight(self.max_step_height),
+                PlayerWidth(self.width),
+                self.xr_locomotion,
+                self.xr_turn,
+            ),
+            (self.input_source, PlayerInputState::default()),
+            PlayerKnockback::default(),
+            PlayerLook::default(),
+            PlayerMovementMode::default(),
+            PlayerUp::default(),
+            RespawnPoint {
+                position: self.spawn,
+                facing: None,
+            },
             RigidBody::Dynamic,
             SpatialBundle {
                 global_transform: GlobalTransform::from_translation(self.spawn),
@@ -59,12 +178,97 @@ impl PlayerSettings {
             TnuaControllerBundle::default(),
         ));
 
+        if let Some(value) = &self.head_collision {
+            body.insert(value.clone());
+        }
+
+        if let Some(value) = &self.vignette {
+            body.insert(value.clone());
+        }
+
         if let Some(value) = self.void_level {
             body.insert(VoidTeleport(value));
         }
 
         let body = body.id();
 
+        let avatar = self.spawn_avatar(commands, body);
+        commands.entity(avatar).insert(FirstPerson);
+
+        let mut camera = commands.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    order: self.split_screen.map_or(0, |split| split.index as isize),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, -self.height / 2.0, 0.0),
+                ..default()
+            },
+            CameraFreeLook(false),
+            PlayerCamera,
+            StepSmoothing::default(),
+            render_layers(),
+        ));
+
+        if let Some(value) = self.split_screen {
+            camera.insert(value);
+        }
+
+        let camera = camera.id();
+
+        commands.entity(body).push_children(&[avatar, camera]);
+
+        SpawnedPlayer {
+            avatar,
+            body,
+            camera,
+        }
+    }
+
+    /// Spawns a player controlled by replicated state, see [RemotePlayer].
+    /// Remote players have a kinematic body, and no camera or first person setup.
+    pub fn spawn_remote(&self, commands: &mut Commands) -> SpawnedRemotePlayer {
+        let body = commands
+            .spawn((
+                player_collider(self.height, self.width, self.max_step_height),
+                PlayerBody,
+                (
+                    self.avatar_scaling,
+                    PlayerCrouch {
+                        height: self.crouch_height,
+                        toggle: self.crouch_toggle,
+                        crouching: false,
+                        amount: 0.0,
+                        pressed: false,
+                    },
+                    PlayerHeight(self.height),
+                    PlayerSpeed(self.speed),
+                    PlayerSprintSpeed(self.sprint_speed),
+                    PlayerStepHeight(self.max_step_height),
+                    PlayerWidth(self.width),
+                ),
+                (PlayerInputSource::None, PlayerInputState::default()),
+                PlayerMovementMode::default(),
+                RemotePlayer {
+                    position: self.spawn,
+                    ..default()
+                },
+                RigidBody::Kinematic,
+                SpatialBundle {
+                    global_transform: GlobalTransform::from_translation(self.spawn),
+                    ..default()
+                },
+            ))
+            .id();
+
+        let avatar = self.spawn_avatar(commands, body);
+
+        commands.entity(body).add_child(avatar);
+
+        SpawnedRemotePlayer { avatar, body }
+    }
+
+    fn spawn_avatar(&self, commands: &mut Commands, body: Entity) -> Entity {
         let mut avatar = commands.spawn((
             AverageVelocity {
                 target: Some(body),
@@ -74,40 +278,23 @@ impl PlayerSettings {
             PlayerHeight(self.height),
             VrmBundle {
                 scene_bundle: SceneBundle {
-                    transform: Transform::from_xyz(0.0, -self.height / 2.0, 0.0),
+                    transform: Transform::from_xyz(
+                        0.0,
+                        -float_height(self.height, self.max_step_height),
+                        0.0,
+                    ),
                     ..default()
                 },
                 vrm: self.vrm.clone().unwrap_or_default(),
                 ..default()
             },
-            FirstPerson,
         ));
 
         if let Some(value) = &self.animations {
             avatar.insert(value.clone());
         }
 
-        let avatar = avatar.id();
-
-        let camera = commands
-            .spawn((
-                Camera3dBundle {
-                    transform: Transform::from_xyz(0.0, -self.height / 2.0, 0.0),
-                    ..default()
-                },
-                CameraFreeLook(false),
-                PlayerCamera,
-                render_layers(),
-            ))
-            .id();
-
-        commands.entity(body).push_children(&[avatar, camera]);
-
-        SpawnedPlayer {
-            avatar,
-            body,
-            camera,
-        }
+        avatar.id()
     }
 }
 
@@ -117,6 +304,11 @@ pub struct SpawnedPlayer {
     pub camera: Entity,
 }
 
+pub struct SpawnedRemotePlayer {
+    pub avatar: Entity,
+    pub body: Entity,
+}
+
 #[cfg(feature = "xr")]
 pub fn set_xr_render_layers(
     mut commands: Commands,
@@ -127,6 +319,17 @@ pub fn set_xr_render_layers(
     }
 }
 
+/// Capsule collider, floating above the ground by the step height so that steps
+/// can be walked over. Players too short for a capsule get a sphere.
+pub(crate) fn player_collider(height: f32, width: f32, step_height: f32) -> Collider {
+    Collider::capsule(width / 2.0, (height - step_height - width).max(0.0))
+}
+
+/// Distance from the center of the player's collider to the ground.
+pub(crate) fn float_height(height: f32, step_height: f32) -> f32 {
+    (height + step_height) / 2.0
+}
+
 fn render_layers() -> RenderLayers {
     RenderLayers::layer(0).union(&RENDER_LAYERS[&FirstPersonFlag::FirstPersonOnly])
 }
@@ -143,19 +346,158 @@ pub struct PlayerCamera;
 #[derive(Component)]
 pub struct PlayerHeight(pub(crate) f32);
 
+#[derive(Component)]
+pub struct PlayerWidth(pub(crate) f32);
+
 #[derive(Component)]
 pub struct PlayerSpeed(pub f32);
 
+#[derive(Component)]
+pub struct PlayerSprintSpeed(pub f32);
+
 #[derive(Component)]
 pub struct PlayerJumpHeight(pub f32);
 
+#[derive(Component)]
+pub struct PlayerJumpSettings {
+    /// See [PlayerSettings::air_jumps].
+    pub air_jumps: u32,
+    /// See [PlayerSettings::coyote_time].
+    pub coyote_time: f32,
+    /// See [PlayerSettings::jump_buffer_time].
+    pub input_buffer_time: f32,
+    /// See [PlayerSettings::variable_jump_height].
+    pub variable_height: bool,
+    /// Whether the current jump is an air jump.
+    pub(crate) air_jump: bool,
+    pub(crate) air_jumps_used: u32,
+    pub(crate) pressed: bool,
+}
+
+/// Initial spawn position of the player.
+/// See [RespawnPoint] for where the player respawns.
 #[derive(Component)]
 pub struct PlayerSpawn(pub Vec3);
 
+#[derive(Component)]
+pub struct PlayerStepHeight(pub(crate) f32);
+
+#[derive(Component)]
+pub struct PlayerSlope {
+    /// See [PlayerSettings::max_slope].
+    pub max_slope: f32,
+    /// See [PlayerSettings::slope_slide_acceleration].
+    pub slide_acceleration: f32,
+}
+
+#[derive(Component)]
+pub struct PlayerCrouch {
+    /// See [PlayerSettings::crouch_height].
+    pub height: f32,
+    /// See [PlayerSettings::crouch_toggle].
+    pub toggle: bool,
+    pub crouching: bool,
+    /// Smoothed crouch amount, from 0 (standing) to 1 (crouched).
+    pub(crate) amount: f32,
+    pub(crate) pressed: bool,
+}
+
+impl PlayerCrouch {
+    /// Height of the player, smoothly interpolated between standing and crouched.
+    pub fn current_height(&self, standing_height: f32) -> f32 {
+        standing_height + (self.height - standing_height) * self.amount
+    }
+}
+
+#[derive(Component)]
+pub struct PlayerDash {
+    /// See [PlayerSettings::dash_in_air].
+    pub allow_in_air: bool,
+    /// See [PlayerSettings::dash_cooldown].
+    pub cooldown: f32,
+    /// See [PlayerSettings::dash_distance].
+    pub distance: f32,
+    /// See [PlayerSettings::dash_duration].
+    pub duration: f32,
+    pub(crate) displacement: Vec3,
+    /// Time the last dash started at.
+    pub(crate) last_dash: Option<f32>,
+}
+
+/// How the avatar and the player are sized to match each other,
+/// once the avatar has loaded.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AvatarScaling {
+    /// Uniformly scale the avatar, so that its eye height matches the player's height.
+    #[default]
+    ScaleAvatar,
+    /// Set the player's height, and the size of its collider, from the avatar.
+    ResizeCollider,
+    /// Keep the avatar at its native scale.
+    None,
+}
+
+/// Locomotion method used in XR.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum XrLocomotion {
+    /// Smooth movement using the thumbsticks.
+    #[default]
+    Smooth,
+    /// Teleport movement, aiming an arc with the thumbstick
+    /// and teleporting on release.
+    Teleport,
+}
+
+/// Turning method used in XR.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum XrTurn {
+    /// Turning is only possible by physically turning.
+    Disabled,
+    /// Turn in fixed steps.
+    Snap {
+        /// Angle of each turn, in degrees.
+        angle: f32,
+        /// Thumbstick deflection needed to turn.
+        deadzone: f32,
+        /// Time, in seconds, between turns while the thumbstick is held.
+        cooldown: f32,
+    },
+    /// Turn continuously.
+    Smooth {
+        /// Turn speed, in degrees per second.
+        speed: f32,
+    },
+}
+
+impl Default for XrTurn {
+    fn default() -> Self {
+        Self::Snap {
+            angle: 45.0,
+            deadzone: 0.5,
+            cooldown: 0.3,
+        }
+    }
+}
+
+/// Look state of a player.
+#[derive(Component, Default)]
+pub struct PlayerLook {
+    /// Accumulated yaw (x) and pitch (y) look input, in radians.
+    pub look_xy: Vec2,
+    /// Yaw at the time free look was enabled.
+    pub(crate) free_yaw: Option<Quat>,
+    pub(crate) target_pitch_roll: Quat,
+    pub(crate) target_yaw: Quat,
+    /// Yaw applied on top of the look input, such as from turning or riding platforms.
+    pub(crate) yaw_offset: Quat,
+}
+
 /// If `true`, unlocks the yaw axis for the camera.
 #[derive(Component)]
 pub struct CameraFreeLook(pub bool);
 
-/// Teleport the player to spawn if they fall below a certain Y level.
+/// Respawn the player if they fall below a certain Y level.
+/// If the player has a [PlayerGravity](crate::gravity::PlayerGravity),
+/// the level is measured against it instead.
 #[derive(Component)]
 pub struct VoidTeleport(pub f32);
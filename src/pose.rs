@@ -0,0 +1,655 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy_vrm::BoneName;
+
+use crate::{
+    animation::{weights::AnimationWeights, AnimationName, AvatarAnimationNodes},
+    player::{PlayerAvatar, PlayerBody, PlayerCamera},
+    remote::RemotePlayer,
+};
+
+const FORMAT_VERSION: u8 = 1;
+
+const FLAG_HANDS: u8 = 1;
+const OTHER_ANIMATION: u8 = u8::MAX;
+
+/// Bones included in a [PoseSnapshot], in encoding order.
+/// New bones must only be appended, to keep the encoding stable.
+const SNAPSHOT_BONES: [BoneName; 54] = [
+    BoneName::Hips,
+    BoneName::Spine,
+    BoneName::Chest,
+    BoneName::UpperChest,
+    BoneName::Neck,
+    BoneName::Head,
+    BoneName::LeftEye,
+    BoneName::RightEye,
+    BoneName::LeftUpperLeg,
+    BoneName::LeftLowerLeg,
+    BoneName::LeftFoot,
+    BoneName::LeftToes,
+    BoneName::RightUpperLeg,
+    BoneName::RightLowerLeg,
+    BoneName::RightFoot,
+    BoneName::RightToes,
+    BoneName::LeftShoulder,
+    BoneName::LeftUpperArm,
+    BoneName::LeftLowerArm,
+    BoneName::LeftHand,
+    BoneName::RightShoulder,
+    BoneName::RightUpperArm,
+    BoneName::RightLowerArm,
+    BoneName::RightHand,
+    BoneName::LeftThumbProximal,
+    BoneName::LeftThumbIntermediate,
+    BoneName::LeftThumbDistal,
+    BoneName::LeftIndexProximal,
+    BoneName::LeftIndexIntermediate,
+    BoneName::LeftIndexDistal,
+    BoneName::LeftMiddleProximal,
+    BoneName::LeftMiddleIntermediate,
+    BoneName::LeftMiddleDistal,
+    BoneName::LeftRingProximal,
+    BoneName::LeftRingIntermediate,
+    BoneName::LeftRingDistal,
+    BoneName::LeftLittleProximal,
+    BoneName::LeftLittleIntermediate,
+    BoneName::LeftLittleDistal,
+    BoneName::RightThumbProximal,
+    BoneName::RightThumbIntermediate,
+    BoneName::RightThumbDistal,
+    BoneName::RightIndexProximal,
+    BoneName::RightIndexIntermediate,
+    BoneName::RightIndexDistal,
+    BoneName::RightMiddleProximal,
+    BoneName::RightMiddleIntermediate,
+    BoneName::RightMiddleDistal,
+    BoneName::RightRingProximal,
+    BoneName::RightRingIntermediate,
+    BoneName::RightRingDistal,
+    BoneName::RightLittleProximal,
+    BoneName::RightLittleIntermediate,
+    BoneName::RightLittleDistal,
+];
+
+/// Built-in animations, in encoding order.
+/// New animations must only be appended, to keep the encoding stable.
+const SNAPSHOT_ANIMATIONS: [AnimationName; 13] = [
+    AnimationName::Climb,
+    AnimationName::Crouch,
+    AnimationName::CrouchWalk,
+    AnimationName::Dash,
+    AnimationName::Falling,
+    AnimationName::Float,
+    AnimationName::Idle,
+    AnimationName::Run,
+    AnimationName::Swim,
+    AnimationName::TreadWater,
+    AnimationName::Walk,
+    AnimationName::WalkLeft,
+    AnimationName::WalkRight,
+];
+
+/// The full pose of a player's avatar, for sending over the network.
+///
+/// Rotations and animation weights are quantized when encoded,
+/// so a decoded snapshot is close to, but not exactly, the original.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoseSnapshot {
+    /// Transform of the player's body. Scale is not included.
+    pub body: Transform,
+    /// Rotation of the head, relative to the body.
+    pub head_rotation: Quat,
+    /// Hand IK targets, relative to the body. Only captured for the XR user's avatar.
+    pub hands: Option<HandTargets>,
+    /// Local rotations of the avatar's bones.
+    pub bones: Vec<(BoneName, Quat)>,
+    /// Weights of the built-in animations.
+    pub weights: Vec<(AnimationName, f32)>,
+    /// Weights of [AnimationName::Other] animations, by name.
+    pub other_weights: Vec<(String, f32)>,
+}
+
+/// Hand IK targets, relative to the body. Scale is not included.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HandTargets {
+    pub left: Transform,
+    pub right: Transform,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoseDecodeError {
+    UnexpectedEnd,
+    UnsupportedVersion(u8),
+    InvalidBone(u8),
+    InvalidAnimation(u8),
+    InvalidName,
+}
+
+impl fmt::Display for PoseDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of pose snapshot"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported pose snapshot version {}", v),
+            Self::InvalidBone(i) => write!(f, "invalid bone index {}", i),
+            Self::InvalidAnimation(i) => write!(f, "invalid animation index {}", i),
+            Self::InvalidName => write!(f, "animation name is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for PoseDecodeError {}
+
+impl PoseSnapshot {
+    /// Weight of an animation, or 0 if it is not in the snapshot.
+    pub fn weight(&self, name: &AnimationName) -> f32 {
+        let weight = match name {
+            AnimationName::Other(other) => self
+                .other_weights
+                .iter()
+                .find(|(n, _)| n == other)
+                .map(|(_, w)| *w),
+            _ => self
+                .weights
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, w)| *w),
+        };
+
+        weight.unwrap_or_default()
+    }
+
+    /// Encodes the snapshot into a stable, little-endian binary format.
+    /// Bones and animations that are not part of the format are skipped.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![FORMAT_VERSION];
+
+        write_vec3(&mut bytes, self.body.translation);
+        write_quat(&mut bytes, self.body.rotation);
+        write_quat(&mut bytes, self.head_rotation);
+
+        let flags = if self.hands.is_some() { FLAG_HANDS } else { 0 };
+        bytes.push(flags);
+
+        if let Some(hands) = &self.hands {
+            for hand in [&hands.left, &hands.right] {
+                write_vec3(&mut bytes, hand.translation);
+                write_quat(&mut bytes, hand.rotation);
+            }
+        }
+
+        let bones = self
+            .bones
+            .iter()
+            .filter_map(|(name, rotation)| {
+                let index = SNAPSHOT_BONES.iter().position(|b| b == name)?;
+                Some((index as u8, *rotation))
+            })
+            .collect::<Vec<_>>();
+
+        bytes.push(bones.len() as u8);
+
+        for (index, rotation) in bones {
+            bytes.push(index);
+            write_quat(&mut bytes, rotation);
+        }
+
+        let weights = self
+            .weights
+            .iter()
+            .filter_map(|(name, weight)| {
+                let index = SNAPSHOT_ANIMATIONS.iter().position(|a| a == name)?;
+                Some((index as u8, *weight))
+            })
+            .collect::<Vec<_>>();
+
+        let other_weights = self
+            .other_weights
+            .iter()
+            .filter(|(name, _)| name.len() <= u8::MAX as usize)
+            .take(u8::MAX as usize - weights.len())
+            .collect::<Vec<_>>();
+
+        bytes.push((weights.len() + other_weights.len()) as u8);
+
+        for (index, weight) in weights {
+            bytes.push(index);
+            bytes.push(quantize_weight(weight));
+        }
+
+        for (name, weight) in other_weights {
+            bytes.push(OTHER_ANIMATION);
+            bytes.push(name.len() as u8);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.push(quantize_weight(*weight));
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, PoseDecodeError> {
+        let mut reader = Reader(bytes);
+
+        let version = reader.u8()?;
+
+        if version != FORMAT_VERSION {
+            return Err(PoseDecodeError::UnsupportedVersion(version));
+        }
+
+        let mut snapshot = PoseSnapshot {
+            body: Transform::from_translation(reader.vec3()?).with_rotation(reader.quat()?),
+            head_rotation: reader.quat()?,
+            ..default()
+        };
+
+        let flags = reader.u8()?;
+
+        if flags & FLAG_HANDS != 0 {
+            let left = Transform::from_translation(reader.vec3()?).with_rotation(reader.quat()?);
+            let right = Transform::from_translation(reader.vec3()?).with_rotation(reader.quat()?);
+            snapshot.hands = Some(HandTargets { left, right });
+        }
+
+        for _ in 0..reader.u8()? {
+            let index = reader.u8()?;
+            let name = SNAPSHOT_BONES
+                .get(index as usize)
+                .cloned()
+                .ok_or(PoseDecodeError::InvalidBone(index))?;
+            snapshot.bones.push((name, reader.quat()?));
+        }
+
+        for _ in 0..reader.u8()? {
+            let index = reader.u8()?;
+
+            if index == OTHER_ANIMATION {
+                let len = reader.u8()?;
+                let name = std::str::from_utf8(reader.take(len as usize)?)
+                    .map_err(|_| PoseDecodeError::InvalidName)?;
+                let weight = dequantize_weight(reader.u8()?);
+                snapshot.other_weights.push((name.to_string(), weight));
+            } else {
+                let name = SNAPSHOT_ANIMATIONS
+                    .get(index as usize)
+                    .cloned()
+                    .ok_or(PoseDecodeError::InvalidAnimation(index))?;
+                let weight = dequantize_weight(reader.u8()?);
+                snapshot.weights.push((name, weight));
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+fn write_vec3(bytes: &mut Vec<u8>, value: Vec3) {
+    for v in value.to_array() {
+        bytes.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+/// Quantizes each component of the normalized quaternion to an i16.
+fn write_quat(bytes: &mut Vec<u8>, value: Quat) {
+    let value = value.normalize();
+
+    // q and -q are the same rotation, keep w positive for consistent output.
+    let value = if value.w < 0.0 { -value } else { value };
+
+    for v in value.to_array() {
+        let q = (v.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        bytes.extend_from_slice(&q.to_le_bytes());
+    }
+}
+
+fn quantize_weight(weight: f32) -> u8 {
+    (weight.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8
+}
+
+fn dequantize_weight(weight: u8) -> f32 {
+    weight as f32 / u8::MAX as f32
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PoseDecodeError> {
+        if self.0.len() < len {
+            return Err(PoseDecodeError::UnexpectedEnd);
+        }
+
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+
+    fn u8(&mut self) -> Result<u8, PoseDecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn f32(&mut self) -> Result<f32, PoseDecodeError> {
+        let bytes = self.take(4)?;
+        Ok(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn i16(&mut self) -> Result<i16, PoseDecodeError> {
+        let bytes = self.take(2)?;
+        Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn vec3(&mut self) -> Result<Vec3, PoseDecodeError> {
+        Ok(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn quat(&mut self) -> Result<Quat, PoseDecodeError> {
+        let mut values = [0.0; 4];
+
+        for v in values.iter_mut() {
+            *v = self.i16()? as f32 / i16::MAX as f32;
+        }
+
+        Ok(Quat::from_array(values).normalize())
+    }
+}
+
+/// Captures the avatar's pose into a [PoseSnapshot] every frame,
+/// after animations and IK have been applied.
+#[derive(Component, Default)]
+pub struct CapturePose(pub Option<PoseSnapshot>);
+
+/// Poses a puppet avatar from a [PoseSnapshot], overriding its animations.
+/// If the avatar belongs to a [RemotePlayer], the body and head rotation are applied to it.
+#[derive(Component)]
+pub struct ApplyPose(pub PoseSnapshot);
+
+pub(crate) fn capture_poses(
+    animation_players: Query<&AnimationWeights>,
+    mut avatars: Query<(Entity, &mut CapturePose, &Parent), With<PlayerAvatar>>,
+    bones: Query<(&BoneName, &Transform)>,
+    cameras: Query<&Transform, With<PlayerCamera>>,
+    children: Query<&Children>,
+    players: Query<(&Transform, &Children), With<PlayerBody>>,
+    #[cfg(feature = "xr")] globals: Query<&GlobalTransform>,
+    #[cfg(feature = "xr")] iks: Query<&crate::ik::HumanoidIK>,
+) {
+    for (entity, mut capture, parent) in avatars.iter_mut() {
+        let Ok((body_tr, body_children)) = players.get(parent.get()) else {
+            continue;
+        };
+
+        let head_rotation = body_children
+            .iter()
+            .find_map(|child| cameras.get(*child).ok())
+            .map_or(Quat::IDENTITY, |camera_tr| camera_tr.rotation);
+
+        let mut snapshot = PoseSnapshot {
+            body: *body_tr,
+            head_rotation,
+            ..default()
+        };
+
+        #[cfg(feature = "xr")]
+        {
+            let ik = iks.iter().find(|ik| ik.avatar == entity);
+
+            if let (Some(ik), Ok(body_global)) = (ik, globals.get(parent.get())) {
+                let relative = |target| {
+                    globals
+                        .get(target)
+                        .ok()
+                        .map(|global| global.reparented_to(body_global))
+                };
+
+                if let (Some(left), Some(right)) =
+                    (relative(ik.left_target), relative(ik.right_target))
+                {
+                    snapshot.hands = Some(HandTargets { left, right });
+                }
+            }
+        }
+
+        for descendant in children.iter_descendants(entity) {
+            if let Ok((name, transform)) = bones.get(descendant) {
+                snapshot.bones.push((name.clone(), transform.rotation));
+            }
+
+            if let Ok(weights) = animation_players.get(descendant) {
+                for (name, weight) in weights.iter() {
+                    match name {
+                        AnimationName::Other(other) => {
+                            snapshot.other_weights.push((other.to_string(), *weight));
+                        }
+                        _ => snapshot.weights.push((name.clone(), *weight)),
+                    }
+                }
+            }
+        }
+
+        capture.0 = Some(snapshot);
+    }
+}
+
+pub(crate) fn apply_poses(
+    mut animation_players: Query<(&mut AnimationWeights, &mut AnimationPlayer)>,
+    avatars: Query<
+        (Entity, &ApplyPose, &Parent, Option<&AvatarAnimationNodes>),
+        With<PlayerAvatar>,
+    >,
+    mut bones: Query<(&BoneName, &mut Transform)>,
+    children: Query<&Children>,
+    mut remotes: Query<&mut RemotePlayer>,
+) {
+    for (entity, pose, parent, nodes) in avatars.iter() {
+        let snapshot = &pose.0;
+
+        if let Ok(mut remote) = remotes.get_mut(parent.get()) {
+            remote.position = snapshot.body.translation;
+            remote.rotation = snapshot.body.rotation;
+            remote.head_rotation = snapshot.head_rotation;
+            remote.hands.clone_from(&snapshot.hands);
+        }
+
+        for descendant in children.iter_descendants(entity) {
+            if let Ok((name, mut transform)) = bones.get_mut(descendant) {
+                if let Some((_, rotation)) = snapshot.bones.iter().find(|(b, _)| b == name) {
+                    transform.rotation = *rotation;
+                }
+            }
+
+            let (Ok((mut weights, mut player)), Some(nodes)) =
+                (animation_players.get_mut(descendant), nodes)
+            else {
+                continue;
+            };
+
+            // Animations the avatar does not have are skipped.
+            for (name, node) in nodes.0.iter() {
+                if player.animation(*node).is_none() {
+                    player.play(*node).repeat();
+                }
+
+                let weight = snapshot.weight(name);
+                player.animation_mut(*node).unwrap().set_weight(weight);
+                weights.insert(name.clone(), weight);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.001;
+
+    fn quat_eq(a: Quat, b: Quat) -> bool {
+        a.angle_between(b) < EPSILON
+    }
+
+    fn sample() -> PoseSnapshot {
+        PoseSnapshot {
+            body: Transform::from_xyz(1.0, -2.5, 30.0).with_rotation(Quat::from_rotation_y(1.2)),
+            head_rotation: Quat::from_euler(EulerRot::YXZ, 0.3, -0.4, 0.1),
+            hands: Some(HandTargets {
+                left: Transform::from_xyz(-0.3, 1.1, -0.2)
+                    .with_rotation(Quat::from_rotation_x(0.5)),
+                right: Transform::from_xyz(0.3, 1.2, -0.4)
+                    .with_rotation(Quat::from_rotation_z(-2.0)),
+            }),
+            bones: vec![
+                (BoneName::Hips, Quat::from_rotation_y(-0.2)),
+                (BoneName::Head, Quat::from_rotation_x(0.7)),
+                (BoneName::RightLittleDistal, Quat::from_rotation_z(3.0)),
+            ],
+            weights: vec![(AnimationName::Idle, 0.25), (AnimationName::Walk, 0.75)],
+            other_weights: vec![("wave".to_string(), 1.0)],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let snapshot = sample();
+        let decoded = PoseSnapshot::decode(&snapshot.encode()).unwrap();
+
+        assert_eq!(decoded.body.translation, snapshot.body.translation);
+        assert!(quat_eq(decoded.body.rotation, snapshot.body.rotation));
+        assert!(quat_eq(decoded.head_rotation, snapshot.head_rotation));
+
+        let hands = snapshot.hands.unwrap();
+        let decoded_hands = decoded.hands.unwrap();
+        assert_eq!(decoded_hands.left.translation, hands.left.translation);
+        assert_eq!(decoded_hands.right.translation, hands.right.translation);
+        assert!(quat_eq(decoded_hands.left.rotation, hands.left.rotation));
+        assert!(quat_eq(decoded_hands.right.rotation, hands.right.rotation));
+
+        assert_eq!(decoded.bones.len(), snapshot.bones.len());
+        for ((name, rotation), (decoded_name, decoded_rotation)) in
+            snapshot.bones.iter().zip(decoded.bones.iter())
+        {
+            assert_eq!(name, decoded_name);
+            assert!(quat_eq(*rotation, *decoded_rotation));
+        }
+
+        assert_eq!(decoded.weights.len(), snapshot.weights.len());
+        for ((name, weight), (decoded_name, decoded_weight)) in
+            snapshot.weights.iter().zip(decoded.weights.iter())
+        {
+            assert_eq!(name, decoded_name);
+            assert!((weight - decoded_weight).abs() < 0.01);
+        }
+
+        assert_eq!(decoded.other_weights, snapshot.other_weights);
+    }
+
+    #[test]
+    fn round_trip_without_hands() {
+        let snapshot = PoseSnapshot {
+            hands: None,
+            ..sample()
+        };
+        let decoded = PoseSnapshot::decode(&snapshot.encode()).unwrap();
+
+        assert!(decoded.hands.is_none());
+        assert_eq!(decoded.bones.len(), snapshot.bones.len());
+    }
+
+    #[test]
+    fn encoding_is_stable() {
+        let bytes = sample().encode();
+        let decoded = PoseSnapshot::decode(&bytes).unwrap();
+
+        assert_eq!(decoded.encode(), bytes);
+    }
+
+    /// Snapshot using only exactly quantizable values, for [GOLDEN].
+    fn golden() -> PoseSnapshot {
+        PoseSnapshot {
+            body: Transform::from_xyz(1.0, -2.5, 30.0)
+                .with_rotation(Quat::from_xyzw(0.0, 0.6, 0.0, 0.8)),
+            head_rotation: Quat::IDENTITY,
+            hands: Some(HandTargets {
+                left: Transform::from_xyz(-0.5, 1.0, -0.25)
+                    .with_rotation(Quat::from_xyzw(0.8, 0.0, 0.0, 0.6)),
+                right: Transform::from_xyz(0.5, 1.25, -0.5)
+                    .with_rotation(Quat::from_xyzw(0.0, 0.0, 0.6, -0.8)),
+            }),
+            bones: vec![
+                (BoneName::Hips, Quat::IDENTITY),
+                (BoneName::Head, Quat::from_xyzw(0.6, 0.0, 0.0, 0.8)),
+                (BoneName::RightLittleDistal, Quat::IDENTITY),
+            ],
+            weights: vec![(AnimationName::Idle, 0.25), (AnimationName::Walk, 0.75)],
+            other_weights: vec![("wave".to_string(), 1.0)],
+        }
+    }
+
+    /// Encoding of [golden], in format version 1.
+    /// If this changes, the format has changed and [FORMAT_VERSION] must be bumped.
+    const GOLDEN: &[u8] = &[
+        1, 0, 0, 128, 63, 0, 0, 32, 192, 0, 0, 240, 65, 0, 0, 204, 76, 0, 0, 102, 102, 0, 0, 0, 0,
+        0, 0, 255, 127, 1, 0, 0, 0, 191, 0, 0, 128, 63, 0, 0, 128, 190, 102, 102, 0, 0, 0, 0, 204,
+        76, 0, 0, 0, 63, 0, 0, 160, 63, 0, 0, 0, 191, 0, 0, 0, 0, 52, 179, 102, 102, 3, 0, 0, 0, 0,
+        0, 0, 0, 255, 127, 5, 204, 76, 0, 0, 0, 0, 102, 102, 53, 0, 0, 0, 0, 0, 0, 255, 127, 3, 6,
+        64, 10, 191, 255, 4, 119, 97, 118, 101, 255,
+    ];
+
+    #[test]
+    fn encoding_matches_golden_bytes() {
+        assert_eq!(golden().encode(), GOLDEN);
+        assert_eq!(PoseSnapshot::decode(GOLDEN).unwrap().encode(), GOLDEN);
+    }
+
+    #[test]
+    fn empty_snapshot() {
+        let bytes = PoseSnapshot::default().encode();
+
+        // Version, position, 2 rotations, flags, bone count, weight count.
+        assert_eq!(bytes.len(), 1 + 12 + 8 + 8 + 1 + 1 + 1);
+        assert_eq!(bytes[0], FORMAT_VERSION);
+
+        let decoded = PoseSnapshot::decode(&bytes).unwrap();
+        assert_eq!(decoded.body.translation, Vec3::ZERO);
+        assert!(decoded.bones.is_empty());
+    }
+
+    #[test]
+    fn truncated_input() {
+        let bytes = sample().encode();
+
+        for len in 0..bytes.len() {
+            assert_eq!(
+                PoseSnapshot::decode(&bytes[..len]),
+                Err(PoseDecodeError::UnexpectedEnd)
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut bytes = sample().encode();
+        bytes[0] = FORMAT_VERSION + 1;
+
+        assert_eq!(
+            PoseSnapshot::decode(&bytes),
+            Err(PoseDecodeError::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn invalid_bone() {
+        let snapshot = PoseSnapshot {
+            bones: vec![(BoneName::Hips, Quat::IDENTITY)],
+            hands: None,
+            ..default()
+        };
+        let mut bytes = snapshot.encode();
+
+        // Bone index follows the header and bone count.
+        bytes[1 + 12 + 8 + 8 + 1 + 1] = SNAPSHOT_BONES.len() as u8;
+
+        assert_eq!(
+            PoseSnapshot::decode(&bytes),
+            Err(PoseDecodeError::InvalidBone(SNAPSHOT_BONES.len() as u8))
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    movement::PlayerInputState, player::PlayerAvatar, pose::HandTargets, velocity::AverageVelocity,
+};
+
+/// Weight of each new snapshot in the estimated offset between the sender's clock and ours.
+const CLOCK_OFFSET_SMOOTHING: f64 = 0.05;
+const MAX_SNAPSHOTS: usize = 64;
+
+/// A player controlled by replicated state, such as another user in a multiplayer world.
+/// Spawned using [PlayerSettings::spawn_remote](crate::player::PlayerSettings::spawn_remote).
+///
+/// Update this component as snapshots arrive, and the player's body, avatar animations,
+/// and head rotation will follow it.
+/// To smooth out network updates, add a [SnapshotInterpolation] to the body
+/// instead of updating it directly.
+#[derive(Component, Clone, Debug, Default)]
+pub struct RemotePlayer {
+    pub position: Vec3,
+    /// Rotation of the body, including any tilt from [PlayerGravity](crate::gravity::PlayerGravity).
+    pub rotation: Quat,
+    /// Rotation of the head, relative to the body.
+    pub head_rotation: Quat,
+    /// Hand targets, relative to the body.
+    /// Not used by the avatar, but available for attaching held items.
+    pub hands: Option<HandTargets>,
+    /// Input of the player, used to crouch and animate the avatar.
+    pub input: PlayerInputState,
+}
+
+/// Timestamped state of a [RemotePlayer], as received over the network.
+#[derive(Clone, Debug, Default)]
+pub struct RemoteSnapshot {
+    /// Time the snapshot was taken, in seconds, on the sender's clock.
+    pub time: f64,
+    pub position: Vec3,
+    /// Rotation of the body, including any tilt from [PlayerGravity](crate::gravity::PlayerGravity).
+    pub rotation: Quat,
+    /// Rotation of the head, relative to the body.
+    pub head_rotation: Quat,
+    /// Hand targets, relative to the body.
+    pub hands: Option<HandTargets>,
+}
+
+/// Buffers [RemoteSnapshot]s and smoothly moves the [RemotePlayer] between them,
+/// rendering it slightly in the past.
+#[derive(Component, Clone, Debug)]
+pub struct SnapshotInterpolation {
+    /// How far behind the sender, in seconds, the player is rendered.
+    /// Should cover a few snapshot intervals, to absorb network jitter.
+    pub delay: f64,
+    /// Maximum time, in seconds, to extrapolate past the latest snapshot
+    /// if no new snapshots arrive. After that, the player stops moving.
+    pub max_extrapolation: f64,
+    clock_offset: Option<f64>,
+    snapshots: VecDeque<RemoteSnapshot>,
+}
+
+impl Default for SnapshotInterpolation {
+    fn default() -> Self {
+        Self::new(0.1, 0.25)
+    }
+}
+
+impl SnapshotInterpolation {
+    pub fn new(delay: f64, max_extrapolation: f64) -> Self {
+        Self {
+            delay,
+            max_extrapolation,
+            clock_offset: None,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    /// Adds a snapshot to the buffer.
+    /// `now` is the current local time in seconds, such as [Time::elapsed_seconds_f64].
+    /// Snapshots may arrive out of order.
+    pub fn push(&mut self, snapshot: RemoteSnapshot, now: f64) {
+        let offset = now - snapshot.time;
+
+        self.clock_offset = Some(match self.clock_offset {
+            Some(prev) => prev + (offset - prev) * CLOCK_OFFSET_SMOOTHING,
+            None => offset,
+        });
+
+        let index = self
+            .snapshots
+            .partition_point(|other| other.time < snapshot.time);
+
+        if self
+            .snapshots
+            .get(index)
+            .is_some_and(|other| other.time == snapshot.time)
+        {
+            return;
+        }
+
+        self.snapshots.insert(index, snapshot);
+
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Clears the buffer, such as after the remote player teleports.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Samples the buffer at a time on the sender's clock,
+    /// returning the interpolated snapshot and velocity.
+    fn sample(&self, time: f64) -> Option<(RemoteSnapshot, Vec3)> {
+        let first = self.snapshots.front()?;
+        let latest = self.snapshots.back()?;
+
+        if self.snapshots.len() == 1 || time <= first.time {
+            return Some((first.clone(), Vec3::ZERO));
+        }
+
+        let limit = latest.time + self.max_extrapolation;
+        let stopped = time > limit;
+        let time = time.min(limit);
+
+        let index = self
+            .snapshots
+            .partition_point(|other| other.time <= time)
+            .clamp(1, self.snapshots.len() - 1);
+
+        let from = &self.snapshots[index - 1];
+        let to = &self.snapshots[index];
+
+        let t = ((time - from.time) / (to.time - from.time)) as f32;
+        let (snapshot, velocity) = interpolate(from, to, t);
+
+        Some((snapshot, if stopped { Vec3::ZERO } else { velocity }))
+    }
+
+    /// Drops snapshots that are no longer needed to sample at `time`.
+    fn prune(&mut self, time: f64) {
+        while self.snapshots.len() > 2 && self.snapshots[1].time <= time {
+            self.snapshots.pop_front();
+        }
+    }
+}
+
+/// Interpolates between two snapshots, extrapolating if `t` is greater than 1.
+fn interpolate(from: &RemoteSnapshot, to: &RemoteSnapshot, t: f32) -> (RemoteSnapshot, Vec3) {
+    let duration = (to.time - from.time) as f32;
+    let velocity = (to.position - from.position) / duration;
+
+    let hands = match (&from.hands, &to.hands) {
+        (Some(a), Some(b)) if t <= 1.0 => Some(HandTargets {
+            left: lerp_transform(&a.left, &b.left, t),
+            right: lerp_transform(&a.right, &b.right, t),
+        }),
+        _ => to.hands.clone(),
+    };
+
+    let snapshot = RemoteSnapshot {
+        time: from.time + (to.time - from.time) * t as f64,
+        position: from.position + (to.position - from.position) * t,
+        rotation: from.rotation.slerp(to.rotation, t.min(1.0)),
+        head_rotation: from.head_rotation.slerp(to.head_rotation, t.min(1.0)),
+        hands,
+    };
+
+    (snapshot, velocity)
+}
+
+fn lerp_transform(a: &Transform, b: &Transform, t: f32) -> Transform {
+    Transform::from_translation(a.translation.lerp(b.translation, t))
+        .with_rotation(a.rotation.slerp(b.rotation, t))
+}
+
+pub(crate) fn interpolate_remote_players(
+    mut avatars: Query<&mut AverageVelocity, With<PlayerAvatar>>,
+    mut players: Query<(&mut SnapshotInterpolation, &mut RemotePlayer, &Children)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    for (mut buffer, mut remote, children) in players.iter_mut() {
+        let Some(offset) = buffer.clock_offset else {
+            continue;
+        };
+
+        let render_time = now - offset - buffer.delay;
+
+        let Some((snapshot, velocity)) = buffer.sample(render_time) else {
+            continue;
+        };
+
+        buffer.prune(render_time);
+
+        remote.position = snapshot.position;
+        remote.rotation = snapshot.rotation;
+        remote.head_rotation = snapshot.head_rotation;
+        remote.hands = snapshot.hands;
+
+        for child in children.iter() {
+            if let Ok(mut average) = avatars.get_mut(*child) {
+                average.input_velocity = Some(velocity);
+            }
+        }
+    }
+}
+
+pub(crate) fn apply_remote_players(
+    mut players: Query<
+        (&RemotePlayer, &mut Transform, &mut PlayerInputState),
+        Changed<RemotePlayer>,
+    >,
+) {
+    for (remote, mut transform, mut input) in players.iter_mut() {
+        transform.translation = remote.position;
+        transform.rotation = remote.rotation;
+        *input = remote.input;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn snapshot(time: f64, x: f32) -> RemoteSnapshot {
+        RemoteSnapshot {
+            time,
+            position: Vec3::new(x, 0.0, 0.0),
+            ..default()
+        }
+    }
+
+    fn times(buffer: &SnapshotInterpolation) -> Vec<f64> {
+        buffer.snapshots.iter().map(|s| s.time).collect()
+    }
+
+    #[test]
+    fn interpolates_between_snapshots() {
+        let mut buffer = SnapshotInterpolation::default();
+        buffer.push(snapshot(0.0, 0.0), 0.0);
+        buffer.push(snapshot(1.0, 10.0), 1.0);
+
+        let (sample, velocity) = buffer.sample(0.25).unwrap();
+
+        assert!((sample.position.x - 2.5).abs() < EPSILON);
+        assert!((velocity.x - 10.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn interpolates_rotation() {
+        let mut buffer = SnapshotInterpolation::default();
+        buffer.push(snapshot(0.0, 0.0), 0.0);
+        buffer.push(
+            RemoteSnapshot {
+                rotation: Quat::from_rotation_z(1.0),
+                ..snapshot(1.0, 0.0)
+            },
+            1.0,
+        );
+
+        let (sample, _) = buffer.sample(0.5).unwrap();
+
+        assert!(sample.rotation.angle_between(Quat::from_rotation_z(0.5)) < EPSILON);
+    }
+
+    #[test]
+    fn holds_first_snapshot_before_buffer() {
+        let mut buffer = SnapshotInterpolation::default();
+        buffer.push(snapshot(1.0, 5.0), 1.0);
+        buffer.push(snapshot(2.0, 10.0), 2.0);
+
+        let (sample, velocity) = buffer.sample(0.0).unwrap();
+
+        assert_eq!(sample.position.x, 5.0);
+        assert_eq!(velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn sorts_out_of_order_and_ignores_duplicates() {
+        let mut buffer = SnapshotInterpolation::default();
+        buffer.push(snapshot(2.0, 20.0), 2.0);
+        buffer.push(snapshot(0.0, 0.0), 2.1);
+        buffer.push(snapshot(1.0, 10.0), 2.2);
+        buffer.push(snapshot(1.0, 99.0), 2.3);
+
+        assert_eq!(times(&buffer), vec![0.0, 1.0, 2.0]);
+
+        let (sample, _) = buffer.sample(1.0).unwrap();
+        assert!((sample.position.x - 10.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn extrapolates_up_to_limit() {
+        let mut buffer = SnapshotInterpolation::new(0.1, 0.25);
+        buffer.push(snapshot(0.0, 0.0), 0.0);
+        buffer.push(snapshot(1.0, 10.0), 1.0);
+
+        let (sample, velocity) = buffer.sample(1.1).unwrap();
+        assert!((sample.position.x - 11.0).abs() < EPSILON);
+        assert!((velocity.x - 10.0).abs() < EPSILON);
+
+        // Past the limit, the last extrapolated pose is held and the player stops.
+        let (sample, velocity) = buffer.sample(5.0).unwrap();
+        assert!((sample.position.x - 12.5).abs() < EPSILON);
+        assert_eq!(velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn prune_keeps_bracketing_snapshot() {
+        let mut buffer = SnapshotInterpolation::default();
+
+        for i in 0..4 {
+            buffer.push(snapshot(i as f64, i as f32 * 10.0), i as f64);
+        }
+
+        buffer.prune(1.5);
+        assert_eq!(times(&buffer), vec![1.0, 2.0, 3.0]);
+
+        let (sample, _) = buffer.sample(1.5).unwrap();
+        assert!((sample.position.x - 15.0).abs() < EPSILON);
+
+        // Two snapshots are always kept, for extrapolation.
+        buffer.prune(10.0);
+        assert_eq!(times(&buffer), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn clock_offset_converges() {
+        let mut buffer = SnapshotInterpolation::default();
+
+        buffer.push(snapshot(0.0, 0.0), 100.0);
+        assert_eq!(buffer.clock_offset, Some(100.0));
+
+        // Jittery arrival times, around a new offset.
+        for i in 1..=300 {
+            let time = i as f64 * 0.05;
+            let jitter = if i % 2 == 0 { 0.01 } else { -0.01 };
+            buffer.push(snapshot(time, 0.0), time + 50.0 + jitter);
+        }
+
+        let offset = buffer.clock_offset.unwrap();
+        assert!((offset - 50.0).abs() < 0.02, "offset {}", offset);
+    }
+
+    #[test]
+    fn applies_full_rotation_to_body() {
+        let mut world = World::new();
+
+        let rotation = Quat::from_rotation_x(0.5) * Quat::from_rotation_y(1.0);
+        let input = PlayerInputState {
+            crouch: true,
+            ..default()
+        };
+
+        let entity = world
+            .spawn((
+                RemotePlayer {
+                    position: Vec3::new(1.0, 2.0, 3.0),
+                    rotation,
+                    input,
+                    ..default()
+                },
+                Transform::default(),
+                PlayerInputState::default(),
+            ))
+            .id();
+
+        world.run_system_once(apply_remote_players);
+
+        let transform = world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+        assert!(transform.rotation.angle_between(rotation) < EPSILON);
+        assert!(world.get::<PlayerInputState>(entity).unwrap().crouch);
+    }
+
+    #[test]
+    fn interpolation_updates_remote_player() {
+        let mut world = World::new();
+
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f64(0.35));
+        world.insert_resource(time);
+
+        let mut buffer = SnapshotInterpolation::new(0.1, 0.25);
+        buffer.push(snapshot(0.1, 0.0), 0.2);
+        buffer.push(snapshot(0.2, 10.0), 0.3);
+
+        let avatar = world.spawn((PlayerAvatar, AverageVelocity::default())).id();
+        let entity = world
+            .spawn((buffer, RemotePlayer::default()))
+            .push_children(&[avatar])
+            .id();
+
+        world.run_system_once(interpolate_remote_players);
+
+        let remote = world.get::<RemotePlayer>(entity).unwrap();
+        assert!((remote.position.x - 5.0).abs() < EPSILON);
+
+        let average = world.get::<AverageVelocity>(avatar).unwrap();
+        assert!((average.input_velocity.unwrap().x - 100.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,47 @@
+//! Render layers reserved by the crate.
+//!
+//! - Layers in [OVERLAY_LAYERS] hold the vignette and fade overlays, one layer per camera,
+//!   so that each overlay is only seen by its own camera.
+//! - Layers from [PLAYER_LAYER_START] onwards hold avatar meshes, two layers per local player,
+//!   so that each player only hides their own head.
+//!
+//! Layers below [OVERLAY_LAYERS] are free for the app to use,
+//! apart from those used by [bevy_vrm::first_person::RENDER_LAYERS].
+
+use std::ops::Range;
+
+/// Render layers used by overlays, one per camera.
+pub const OVERLAY_LAYERS: Range<usize> = 16..PLAYER_LAYER_START;
+
+/// First render layer used by players' avatars.
+pub const PLAYER_LAYER_START: usize = 256;
+
+/// Overlay layer of the `index`th camera, or `None` if all overlay layers are in use.
+pub(crate) fn overlay_layer(index: usize) -> Option<usize> {
+    Some(OVERLAY_LAYERS.start + index).filter(|layer| OVERLAY_LAYERS.contains(layer))
+}
+
+/// First and third person layers of the `index`th player.
+pub(crate) fn player_layers(index: usize) -> (usize, usize) {
+    let first_person = PLAYER_LAYER_START + index * 2;
+    (first_person, first_person + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_layers_run_out_before_player_layers() {
+        assert_eq!(overlay_layer(0), Some(OVERLAY_LAYERS.start));
+
+        let count = OVERLAY_LAYERS.len();
+        assert_eq!(overlay_layer(count - 1), Some(PLAYER_LAYER_START - 1));
+        assert_eq!(overlay_layer(count), None);
+
+        assert_eq!(
+            player_layers(0),
+            (PLAYER_LAYER_START, PLAYER_LAYER_START + 1)
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{player::PlayerBody, teleport::TeleportPlayer};
+
+/// Where a player respawns.
+#[derive(Component, Clone, Debug)]
+pub struct RespawnPoint {
+    pub position: Vec3,
+    /// Orientation to face after respawning.
+    /// If `None`, the current orientation is kept.
+    pub facing: Option<Quat>,
+}
+
+/// Sets the respawn point of players that touch it to its own position and rotation.
+/// Should be added to an entity with a [Sensor] collider.
+#[derive(Component)]
+pub struct Checkpoint;
+
+/// Respawns players that touch it.
+/// Should be added to an entity with a [Sensor] collider.
+#[derive(Component)]
+pub struct KillVolume;
+
+/// Respawns a player at their [RespawnPoint].
+#[derive(Event, Debug, Clone)]
+pub struct RespawnPlayer {
+    pub entity: Entity,
+}
+
+/// Marks a player that has respawned but has not yet gone through a physics step,
+/// so its [CollidingEntities] may still contain the kill volume it respawned from.
+#[derive(Component)]
+pub(crate) struct Respawning;
+
+/// Sent after a player has respawned.
+#[derive(Event, Debug, Clone)]
+pub struct PlayerRespawned {
+    pub entity: Entity,
+    pub position: Vec3,
+}
+
+pub(crate) fn update_checkpoints(
+    checkpoints: Query<&GlobalTransform, With<Checkpoint>>,
+    mut players: Query<(&CollidingEntities, &mut RespawnPoint), With<PlayerBody>>,
+) {
+    for (colliding, mut point) in players.iter_mut() {
+        let Some(checkpoint_tr) = colliding.iter().find_map(|e| checkpoints.get(*e).ok()) else {
+            continue;
+        };
+
+        let (_, rotation, position) = checkpoint_tr.to_scale_rotation_translation();
+
+        point.position = position;
+        point.facing = Some(rotation);
+    }
+}
+
+pub(crate) fn apply_kill_volumes(
+    mut events: EventWriter<RespawnPlayer>,
+    kill_volumes: Query<(), With<KillVolume>>,
+    players: Query<(Entity, &CollidingEntities), (With<PlayerBody>, Without<Respawning>)>,
+) {
+    for (entity, colliding) in players.iter() {
+        if colliding.iter().any(|e| kill_volumes.contains(*e)) {
+            debug!("Player entered a kill volume! Respawning player...");
+            events.send(RespawnPlayer { entity });
+        }
+    }
+}
+
+pub(crate) fn respawn_players(
+    mut commands: Commands,
+    mut events: EventReader<RespawnPlayer>,
+    players: Query<&RespawnPoint, With<PlayerBody>>,
+    mut respawned: EventWriter<PlayerRespawned>,
+    mut teleport: EventWriter<TeleportPlayer>,
+) {
+    let mut handled = HashSet::new();
+
+    for event in events.read() {
+        if !handled.insert(event.entity) {
+            continue;
+        }
+
+        let Ok(point) = players.get(event.entity) else {
+            warn!("Respawn target {} is not a player", event.entity);
+            continue;
+        };
+
+        commands.entity(event.entity).insert(Respawning);
+
+        teleport.send(TeleportPlayer {
+            entity: event.entity,
+            position: point.position,
+            facing: point.facing,
+        });
+
+        respawned.send(PlayerRespawned {
+            entity: event.entity,
+            position: point.position,
+        });
+    }
+}
+
+pub(crate) fn clear_respawning(mut commands: Commands, players: Query<Entity, With<Respawning>>) {
+    for entity in players.iter() {
+        commands.entity(entity).remove::<Respawning>();
+    }
+}
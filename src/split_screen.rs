@@ -0,0 +1,50 @@
+use bevy::{prelude::*, render::camera::Viewport, window::PrimaryWindow};
+
+/// Renders a player's camera to a slice of the primary window, for local split-screen.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SplitScreen {
+    /// Index of the player's slice, from 0 to `count - 1`.
+    pub index: u32,
+    /// Number of players sharing the window, up to 4.
+    pub count: u32,
+}
+
+pub(crate) fn update_split_screen_viewports(
+    mut cameras: Query<(&mut Camera, &SplitScreen)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let size = window.physical_size();
+
+    for (mut camera, split) in cameras.iter_mut() {
+        let (columns, rows) = match split.count {
+            0 | 1 => (1, 1),
+            2 => (2, 1),
+            _ => (2, 2),
+        };
+
+        let cell = UVec2::new(size.x / columns, size.y / rows);
+
+        if cell.x == 0 || cell.y == 0 {
+            continue;
+        }
+
+        let index = split.index.min(columns * rows - 1);
+        let position = UVec2::new(index % columns * cell.x, index / columns * cell.y);
+
+        let unchanged = camera.viewport.as_ref().is_some_and(|viewport| {
+            viewport.physical_position == position && viewport.physical_size == cell
+        });
+
+        if !unchanged {
+            camera.viewport = Some(Viewport {
+                physical_position: position,
+                physical_size: cell,
+                ..default()
+            });
+        }
+    }
+}
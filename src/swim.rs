@@ -0,0 +1,151 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    movement::{PlayerInputState, PlayerMovementMode},
+    player::{PlayerBody, PlayerCamera, PlayerHeight, PlayerSpeed, PlayerSprintSpeed},
+};
+
+/// A body of water players can swim in.
+/// Should be added to an entity with a [Sensor] collider.
+/// The top of the collider's AABB is used as the water's surface.
+#[derive(Component)]
+pub struct WaterVolume {
+    /// How quickly the player's velocity approaches the desired swimming velocity.
+    pub drag: f32,
+}
+
+impl Default for WaterVolume {
+    fn default() -> Self {
+        Self { drag: 3.0 }
+    }
+}
+
+/// Depth below the surface the player starts swimming at, as a fraction of their height.
+const ENTER_DEPTH: f32 = 0.2;
+/// Depth below the surface the player stops swimming at, as a fraction of their height.
+const EXIT_DEPTH: f32 = 0.1;
+/// Depth below the surface the player floats at, as a fraction of their height.
+const FLOAT_DEPTH: f32 = 0.25;
+
+const BUOYANCY: f32 = 4.0;
+const SWIM_SPEED_FACTOR: f32 = 0.6;
+
+pub(crate) fn update_swim_mode(
+    mut players: Query<
+        (
+            &CollidingEntities,
+            &Position,
+            &PlayerHeight,
+            &mut PlayerMovementMode,
+        ),
+        With<PlayerBody>,
+    >,
+    waters: Query<&ColliderAabb, With<WaterVolume>>,
+) {
+    for (colliding, position, height, mut mode) in players.iter_mut() {
+        let surface = colliding
+            .iter()
+            .filter_map(|e| waters.get(*e).ok().map(|aabb| (*e, aabb.max.y)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let current = *mode;
+
+        match current {
+            PlayerMovementMode::Walk => {
+                if let Some((water, surface)) = surface {
+                    if position.0.y < surface - height.0 * ENTER_DEPTH {
+                        *mode = PlayerMovementMode::Swim { water };
+                    }
+                }
+            }
+            PlayerMovementMode::Swim { .. } => match surface {
+                Some((water, surface)) if position.0.y < surface - height.0 * EXIT_DEPTH => {
+                    mode.set_if_neq(PlayerMovementMode::Swim { water });
+                }
+                _ => {
+                    *mode = PlayerMovementMode::Walk;
+                }
+            },
+            PlayerMovementMode::Fly { .. } | PlayerMovementMode::Climb { .. } => {}
+        }
+    }
+}
+
+pub(crate) fn swim_player(
+    cameras: Query<&Transform, (With<PlayerCamera>, Without<PlayerBody>)>,
+    mut players: Query<
+        (
+            &Transform,
+            &Children,
+            &PlayerInputState,
+            &PlayerMovementMode,
+            &PlayerHeight,
+            &PlayerSpeed,
+            &PlayerSprintSpeed,
+            &mut LinearVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    time: Res<Time>,
+    waters: Query<(&WaterVolume, &ColliderAabb)>,
+) {
+    for (transform, children, input, mode, height, speed, sprint_speed, mut linvel) in
+        players.iter_mut()
+    {
+        let PlayerMovementMode::Swim { water: water_ent } = mode else {
+            continue;
+        };
+
+        let Ok((water, aabb)) = waters.get(*water_ent) else {
+            continue;
+        };
+
+        let camera_rotation = children
+            .iter()
+            .find_map(|c| cameras.get(*c).ok())
+            .map(|camera_tr| camera_tr.rotation)
+            .unwrap_or_default();
+
+        let rotation = transform.rotation * camera_rotation;
+
+        let dir_forward = rotation.mul_vec3(Vec3::NEG_Z);
+        let dir_left = rotation.mul_vec3(Vec3::NEG_X);
+
+        let mut move_direction = Vec3::ZERO;
+
+        move_direction += dir_forward * input.forward;
+        move_direction += dir_left * input.left;
+        move_direction += Vec3::Y * input.up;
+
+        let speed = if input.sprint {
+            sprint_speed.0
+        } else {
+            speed.0
+        };
+
+        let speed = speed * SWIM_SPEED_FACTOR;
+
+        let mut desired_velocity = move_direction.normalize_or_zero() * speed;
+
+        // Positive when below the floating depth.
+        let depth = aabb.max.y - height.0 * FLOAT_DEPTH - transform.translation.y;
+
+        if input.jump {
+            desired_velocity.y = speed;
+        } else if depth < 0.0 || move_direction == Vec3::ZERO {
+            // Float towards the surface, without leaving the water.
+            let buoyancy = (depth * BUOYANCY).clamp(-speed, speed);
+
+            desired_velocity.y = if depth < 0.0 {
+                desired_velocity.y.min(buoyancy)
+            } else {
+                buoyancy
+            };
+        }
+
+        let lerp_factor = (time.delta_seconds() * water.drag).min(1.0);
+
+        linvel.0 = linvel.0.lerp(desired_velocity, lerp_factor);
+    }
+}
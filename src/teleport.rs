@@ -0,0 +1,101 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::prelude::*;
+
+use crate::{
+    gravity::PlayerUp,
+    head::StepSmoothing,
+    impulse::PlayerKnockback,
+    look::ResetLookEvent,
+    player::{PlayerAvatar, PlayerBody, PlayerCamera},
+    velocity::AverageVelocity,
+};
+
+/// Teleports a player, resetting their velocity and movement state.
+#[derive(Event, Debug, Clone)]
+pub struct TeleportPlayer {
+    pub entity: Entity,
+    pub position: Vec3,
+    /// Orientation for the player to face.
+    /// Only the yaw is used, relative to the player's up direction.
+    /// If `None`, the current orientation is kept.
+    pub facing: Option<Quat>,
+}
+
+/// Sent after a player has been teleported.
+#[derive(Event, Debug, Clone)]
+pub struct PlayerTeleported {
+    pub entity: Entity,
+    pub from: Vec3,
+    pub to: Vec3,
+}
+
+pub(crate) fn teleport_players(
+    mut avatars: Query<&mut AverageVelocity, With<PlayerAvatar>>,
+    mut cameras: Query<&mut StepSmoothing, With<PlayerCamera>>,
+    mut commands: Commands,
+    mut events: EventReader<TeleportPlayer>,
+    mut look_events: EventWriter<ResetLookEvent>,
+    mut players: Query<
+        (
+            &mut Transform,
+            &Children,
+            &PlayerUp,
+            &mut PlayerKnockback,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        With<PlayerBody>,
+    >,
+    mut teleported: EventWriter<PlayerTeleported>,
+) {
+    for event in events.read() {
+        let Ok((mut transform, children, up, mut knockback, mut linvel, mut angvel)) =
+            players.get_mut(event.entity)
+        else {
+            warn!("Teleport target {} is not a player", event.entity);
+            continue;
+        };
+
+        let from = transform.translation;
+        transform.translation = event.position;
+
+        let yaw = event.facing.map(|facing| {
+            let forward = up.0.inverse() * (facing * Vec3::NEG_Z);
+            f32::atan2(-forward.x, -forward.z)
+        });
+
+        if let Some(yaw) = yaw {
+            transform.rotation = up.0 * Quat::from_rotation_y(yaw);
+        }
+
+        linvel.0 = Vec3::ZERO;
+        angvel.0 = Vec3::ZERO;
+        knockback.cancel();
+
+        commands
+            .entity(event.entity)
+            .insert(TnuaController::default());
+
+        for child in children.iter() {
+            if let Ok(mut average) = avatars.get_mut(*child) {
+                average.initialized = false;
+            }
+
+            if let Ok(mut smoothing) = cameras.get_mut(*child) {
+                *smoothing = StepSmoothing::default();
+            }
+        }
+
+        look_events.send(ResetLookEvent {
+            entity: event.entity,
+            yaw,
+        });
+
+        teleported.send(PlayerTeleported {
+            entity: event.entity,
+            from,
+            to: event.position,
+        });
+    }
+}
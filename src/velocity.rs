@@ -6,6 +6,9 @@ use bevy::prelude::*;
 pub struct AverageVelocity {
     pub alpha: f32,
     pub initialized: bool,
+    /// Velocity to average instead of the change in [Transform],
+    /// such as from [SnapshotInterpolation](crate::remote::SnapshotInterpolation).
+    pub input_velocity: Option<Vec3>,
     pub prev_translation: Vec3,
     /// The target entity to track the velocity of.
     /// If set to None, the current entity will be used.
@@ -18,6 +21,7 @@ impl Default for AverageVelocity {
         Self {
             alpha: 0.1,
             initialized: false,
+            input_velocity: None,
             prev_translation: Vec3::default(),
             target: None,
             velocity: Vec3::default(),
@@ -45,7 +49,9 @@ pub fn calc_average_velocity(
             continue;
         }
 
-        let velocity = (transform.translation - avg.prev_translation) / delta_t;
+        let velocity = avg
+            .input_velocity
+            .unwrap_or((transform.translation - avg.prev_translation) / delta_t);
         avg.prev_translation.clone_from(&transform.translation);
 
         avg.velocity.x = avg.alpha * velocity.x + (1.0 - avg.alpha) * avg.velocity.x;
@@ -0,0 +1,322 @@
+use bevy::{
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::RenderLayers,
+    },
+    utils::HashMap,
+};
+
+use crate::{
+    head_collision::HeadCollision,
+    input::XrUser,
+    look::RotateYawEvent,
+    player::{PlayerAvatar, PlayerBody, PlayerCamera},
+    render_layers::overlay_layer,
+    velocity::AverageVelocity,
+};
+
+/// Darkens the edges of the player's view during artificial movement and turning,
+/// to reduce motion sickness.
+#[derive(Component, Clone, Debug)]
+pub struct ComfortVignette {
+    /// Maximum opacity of the vignette, from 0 to 1.
+    pub max_strength: f32,
+    /// Speed, in meters per second, at which the vignette reaches full strength.
+    pub full_speed: f32,
+    /// Turn speed, in radians per second, at which the vignette reaches full strength.
+    pub full_turn_speed: f32,
+}
+
+impl Default for ComfortVignette {
+    fn default() -> Self {
+        Self {
+            max_strength: 0.9,
+            full_speed: 6.0,
+            full_turn_speed: 2.0,
+        }
+    }
+}
+
+/// Distance of the overlays from the camera.
+const OVERLAY_DISTANCE: f32 = 0.2;
+/// Field of view, in radians, assumed for cameras without a [Projection], such as XR cameras.
+const FALLBACK_FOV: f32 = 2.2;
+/// Extra size of the overlays, so that their edges stay out of view.
+const OVERLAY_MARGIN: f32 = 1.05;
+/// How quickly the vignette fades out once movement stops.
+const FADE_SPEED: f32 = 2.0;
+const TEXTURE_SIZE: u32 = 128;
+
+/// Materials of a player's overlays, shared by all of the player's cameras.
+#[derive(Component, Clone)]
+pub(crate) struct PlayerOverlays {
+    /// Fades the whole view to black.
+    pub fade: Handle<StandardMaterial>,
+    pub vignette: Handle<StandardMaterial>,
+    pub vignette_amount: f32,
+}
+
+/// Marks a camera that already has overlays.
+#[derive(Component)]
+pub(crate) struct OverlayCamera;
+
+/// A quad covering a camera's view, at a distance from the camera.
+#[derive(Component)]
+pub(crate) struct Overlay(f32);
+
+/// Adds vignette and fade overlays to player cameras.
+pub(crate) fn setup_overlays(
+    cameras: Query<
+        (Entity, &RenderLayers, Option<&Parent>),
+        (With<Camera>, Without<OverlayCamera>),
+    >,
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut next_layer: Local<usize>,
+    player_cameras: Query<(), With<PlayerCamera>>,
+    players: Query<
+        (Entity, Has<XrUser>, Option<&PlayerOverlays>),
+        (
+            With<PlayerBody>,
+            Or<(With<ComfortVignette>, With<HeadCollision>)>,
+        ),
+    >,
+    #[cfg(feature = "xr")] xr_cameras: Query<(), With<bevy_mod_xr::camera::XrCamera>>,
+) {
+    let mut created = HashMap::<Entity, PlayerOverlays>::default();
+
+    for (entity, layers, parent) in cameras.iter() {
+        #[cfg(feature = "xr")]
+        let is_xr = xr_cameras.contains(entity);
+        #[cfg(not(feature = "xr"))]
+        let is_xr = false;
+
+        let player = if player_cameras.contains(entity) {
+            parent.map(|parent| parent.get())
+        } else if is_xr {
+            // XR cameras belong to the XR user's player.
+            players
+                .iter()
+                .find(|(_, xr_user, _)| *xr_user)
+                .map(|(player, ..)| player)
+        } else {
+            None
+        };
+
+        let Some((player, _, overlays)) = player.and_then(|player| players.get(player).ok()) else {
+            continue;
+        };
+
+        let Some(layer) = overlay_layer(*next_layer) else {
+            warn!(
+                "Out of overlay render layers, camera {} has no overlays",
+                entity
+            );
+            commands.entity(entity).insert(OverlayCamera);
+            continue;
+        };
+        *next_layer += 1;
+
+        let existing = overlays.or(created.get(&player)).cloned();
+
+        let overlays = match existing {
+            Some(overlays) => overlays,
+            None => {
+                let overlays = PlayerOverlays {
+                    fade: materials.add(StandardMaterial {
+                        alpha_mode: AlphaMode::Blend,
+                        base_color: Color::BLACK.with_alpha(0.0),
+                        unlit: true,
+                        ..default()
+                    }),
+                    vignette: materials.add(StandardMaterial {
+                        alpha_mode: AlphaMode::Blend,
+                        base_color: Color::BLACK.with_alpha(0.0),
+                        base_color_texture: Some(images.add(vignette_image())),
+                        unlit: true,
+                        ..default()
+                    }),
+                    vignette_amount: 0.0,
+                };
+                commands.entity(player).insert(overlays.clone());
+                created.insert(player, overlays.clone());
+                overlays
+            }
+        };
+
+        let mesh = meshes.add(Rectangle::new(1.0, 1.0));
+
+        let vignette = commands
+            .spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: overlays.vignette,
+                    transform: Transform::from_xyz(0.0, 0.0, -OVERLAY_DISTANCE),
+                    ..default()
+                },
+                NotShadowCaster,
+                Overlay(OVERLAY_DISTANCE),
+                RenderLayers::layer(layer),
+            ))
+            .id();
+
+        // Placed slightly closer, so it is drawn over the vignette.
+        let fade = commands
+            .spawn((
+                PbrBundle {
+                    mesh,
+                    material: overlays.fade,
+                    transform: Transform::from_xyz(0.0, 0.0, -OVERLAY_DISTANCE * 0.9),
+                    ..default()
+                },
+                NotShadowCaster,
+                Overlay(OVERLAY_DISTANCE * 0.9),
+                RenderLayers::layer(layer),
+            ))
+            .id();
+
+        commands
+            .entity(entity)
+            .insert((layers.clone().with(layer), OverlayCamera))
+            .push_children(&[vignette, fade]);
+    }
+}
+
+/// Scales overlays to cover their camera's view.
+pub(crate) fn fit_overlays(
+    mut overlays: Query<(&Overlay, &mut Transform, &Parent)>,
+    projections: Query<&Projection>,
+) {
+    for (overlay, mut transform, parent) in overlays.iter_mut() {
+        let size = match projections.get(parent.get()) {
+            Ok(Projection::Perspective(perspective)) => {
+                view_size(perspective.fov, perspective.aspect_ratio, overlay.0)
+            }
+            Ok(Projection::Orthographic(orthographic)) => orthographic.area.size(),
+            Err(_) => view_size(FALLBACK_FOV, 1.0, overlay.0),
+        };
+
+        let scale = (size * OVERLAY_MARGIN).extend(1.0);
+
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+/// Size of the view at a distance from a perspective camera.
+fn view_size(fov: f32, aspect_ratio: f32, distance: f32) -> Vec2 {
+    let height = 2.0 * distance * (fov / 2.0).tan();
+    Vec2::new(height * aspect_ratio, height)
+}
+
+pub(crate) fn update_vignette(
+    avatars: Query<(&AverageVelocity, &Parent), With<PlayerAvatar>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut players: Query<(&ComfortVignette, &mut PlayerOverlays)>,
+    time: Res<Time>,
+    mut yaw_events: EventReader<RotateYawEvent>,
+) {
+    let delta = time.delta_seconds();
+
+    let mut turns = HashMap::<Entity, f32>::default();
+
+    for event in yaw_events.read() {
+        *turns.entry(event.entity).or_default() += event.yaw.abs();
+    }
+
+    for (average, parent) in avatars.iter() {
+        let Ok((vignette, mut overlays)) = players.get_mut(parent.get()) else {
+            continue;
+        };
+
+        let turn = turns.get(&parent.get()).copied().unwrap_or_default();
+        let turn_speed = if delta > 0.0 { turn / delta } else { 0.0 };
+
+        let speed = average.velocity.length() / vignette.full_speed.max(f32::EPSILON);
+        let turn = turn_speed / vignette.full_turn_speed.max(f32::EPSILON);
+        let target = speed.max(turn).min(1.0) * vignette.max_strength;
+
+        overlays.vignette_amount = target.max(overlays.vignette_amount - delta * FADE_SPEED);
+
+        if let Some(material) = materials.get_mut(&overlays.vignette) {
+            material.base_color.set_alpha(overlays.vignette_amount);
+        }
+    }
+}
+
+/// Black texture, transparent in the center and fading to opaque towards the edges.
+fn vignette_image() -> Image {
+    let mut data = Vec::with_capacity((TEXTURE_SIZE * TEXTURE_SIZE * 4) as usize);
+
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            let uv = Vec2::new(x as f32, y as f32) / (TEXTURE_SIZE - 1) as f32;
+            let distance = (uv * 2.0 - 1.0).length();
+            let alpha = ((distance - 0.4) / 0.5).clamp(0.0, 1.0);
+            let alpha = alpha * alpha * (3.0 - 2.0 * alpha);
+
+            data.extend_from_slice(&[0, 0, 0, (alpha * 255.0) as u8]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: TEXTURE_SIZE,
+            height: TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alpha_at(image: &Image, uv: Vec2) -> u8 {
+        let pixel = (uv * (TEXTURE_SIZE - 1) as f32).round().as_uvec2();
+        image.data[((pixel.y * TEXTURE_SIZE + pixel.x) * 4 + 3) as usize]
+    }
+
+    #[test]
+    fn overlay_matches_view() {
+        let fov = PerspectiveProjection::default().fov;
+        let size = view_size(fov, 2.0, OVERLAY_DISTANCE);
+
+        let half_angle = (size.y / 2.0 / OVERLAY_DISTANCE).atan();
+        assert!((half_angle * 2.0 - fov).abs() < 0.0001);
+        assert!((size.x - size.y * 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn vignette_is_opaque_at_view_edge() {
+        let image = vignette_image();
+
+        // The overlay is slightly larger than the view, so the view's edge is inside it.
+        let edge = 0.5 / OVERLAY_MARGIN;
+
+        for uv in [
+            Vec2::new(0.5 - edge, 0.5),
+            Vec2::new(0.5 + edge, 0.5),
+            Vec2::new(0.5, 0.5 - edge),
+            Vec2::new(0.5, 0.5 + edge),
+        ] {
+            assert_eq!(alpha_at(&image, uv), 255, "uv {}", uv);
+        }
+    }
+
+    #[test]
+    fn vignette_is_transparent_at_center() {
+        let image = vignette_image();
+        assert_eq!(alpha_at(&image, Vec2::splat(0.5)), 0);
+    }
+}
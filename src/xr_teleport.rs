@@ -0,0 +1,181 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_xr_utils::{tracking_utils::XrTrackedLeftGrip, xr_utils_actions::XRUtilsActionState};
+
+use crate::{
+    gravity::PlayerGravity,
+    input::{xr::MoveAction, XrUser},
+    player::{float_height, PlayerBody, PlayerHeight, PlayerSlope, PlayerStepHeight, XrLocomotion},
+    teleport::TeleportPlayer,
+};
+
+/// Thumbstick deflection needed to start aiming.
+const AIM_THRESHOLD: f32 = 0.5;
+const ARC_GRAVITY: f32 = 9.81;
+const ARC_SEGMENTS: usize = 40;
+const ARC_SEGMENT_TIME: f32 = 0.05;
+/// Launch speed of the arc, in meters per second.
+const ARC_SPEED: f32 = 8.0;
+
+/// Tracked controller the teleport arc is cast from.
+#[derive(Component)]
+pub(crate) struct XrTeleportAim;
+
+/// Marks the landing point of an in-progress teleport.
+#[derive(Component)]
+pub(crate) struct XrTeleportMarker;
+
+pub(crate) fn setup_xr_teleport(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Cylinder::new(0.3, 0.02))),
+            material: materials.add(StandardMaterial {
+                alpha_mode: AlphaMode::Blend,
+                base_color: Color::srgba(0.3, 0.7, 1.0, 0.6),
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        XrTeleportMarker,
+    ));
+
+    commands.spawn((SpatialBundle::default(), XrTeleportAim, XrTrackedLeftGrip));
+}
+
+pub(crate) fn aim_xr_teleport(
+    mut events: EventWriter<TeleportPlayer>,
+    aims: Query<&GlobalTransform, With<XrTeleportAim>>,
+    mut markers: Query<(&mut Transform, &mut Visibility), With<XrTeleportMarker>>,
+    move_action: Query<&XRUtilsActionState, With<MoveAction>>,
+    players: Query<
+        (
+            Entity,
+            &Collider,
+            Option<&PlayerGravity>,
+            &PlayerHeight,
+            &PlayerSlope,
+            &PlayerStepHeight,
+            &XrLocomotion,
+        ),
+        (With<PlayerBody>, With<XrUser>),
+    >,
+    spatial_query: SpatialQuery,
+    mut target: Local<Option<Vec3>>,
+) {
+    let Ok((entity, collider, gravity, height, slope, step, locomotion)) = players.get_single()
+    else {
+        return;
+    };
+
+    let Ok((mut marker_tr, mut visibility)) = markers.get_single_mut() else {
+        return;
+    };
+
+    if *locomotion != XrLocomotion::Teleport {
+        *target = None;
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let stick = move_action
+        .iter()
+        .find_map(|state| match state {
+            XRUtilsActionState::Vector(value) => Some(Vec2::from(value.current_state)),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+    let up_at = |position: Vec3| gravity.map_or(Vec3::Y, |g| g.up(position));
+    let float = float_height(height.0, step.0);
+
+    if stick.length() >= AIM_THRESHOLD {
+        *target = aims.get_single().ok().and_then(|aim| {
+            let origin = aim.translation();
+            let (point, normal) = cast_arc(
+                origin,
+                *aim.forward(),
+                up_at(origin),
+                &filter,
+                &spatial_query,
+            )?;
+
+            let up = up_at(point);
+
+            // Reject slopes the player could not stand on.
+            if normal.dot(up) < slope.max_slope.cos() {
+                return None;
+            }
+
+            // Reject points where the player would not fit.
+            let rotation = Quat::from_rotation_arc(Vec3::Y, up);
+            let blocked = !spatial_query
+                .shape_intersections(collider, point + up * float, rotation, filter.clone())
+                .is_empty();
+
+            if blocked {
+                None
+            } else {
+                Some(point)
+            }
+        });
+
+        if let Some(point) = *target {
+            marker_tr.translation = point;
+            marker_tr.rotation = Quat::from_rotation_arc(Vec3::Y, up_at(point));
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+
+        return;
+    }
+
+    *visibility = Visibility::Hidden;
+
+    // Teleport once the thumbstick is released.
+    if let Some(point) = target.take() {
+        events.send(TeleportPlayer {
+            entity,
+            position: point + up_at(point) * float,
+            facing: None,
+        });
+    }
+}
+
+/// Casts a parabolic arc, returning the point and normal of the first hit.
+fn cast_arc(
+    origin: Vec3,
+    direction: Vec3,
+    up: Vec3,
+    filter: &SpatialQueryFilter,
+    spatial_query: &SpatialQuery,
+) -> Option<(Vec3, Vec3)> {
+    let mut position = origin;
+    let mut velocity = direction * ARC_SPEED;
+
+    for _ in 0..ARC_SEGMENTS {
+        velocity -= up * ARC_GRAVITY * ARC_SEGMENT_TIME;
+        let step = velocity * ARC_SEGMENT_TIME;
+
+        let Ok(dir) = Dir3::new(step) else {
+            continue;
+        };
+
+        if let Some(hit) =
+            spatial_query.cast_ray(position, dir, step.length(), true, filter.clone())
+        {
+            return Some((position + dir * hit.time_of_impact, hit.normal));
+        }
+
+        position += step;
+    }
+
+    None
+}